@@ -0,0 +1,84 @@
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encode bytes using the standard base64 alphabet, with padding.
+pub(crate) fn encode(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+
+    encoded
+}
+
+/// Decode a string encoded with the standard base64 alphabet, with padding. Returns `None` if the
+/// string is not valid base64.
+pub(crate) fn decode(encoded: &str) -> Option<Vec<u8>> {
+    let bytes = encoded.as_bytes();
+    if !bytes.len().is_multiple_of(4) {
+        return None;
+    }
+
+    let mut decoded = Vec::with_capacity(bytes.len() / 4 * 3);
+
+    for (i, chunk) in bytes.chunks(4).enumerate() {
+        let is_last = i == bytes.len() / 4 - 1;
+        let padding = chunk.iter().rev().take_while(|b| **b == b'=').count();
+        if padding > 2 || (padding > 0 && !is_last) {
+            return None;
+        }
+
+        let mut n = 0u32;
+        for byte in &chunk[..4 - padding] {
+            let value = ALPHABET.iter().position(|a| a == byte)? as u32;
+            n = n << 6 | value;
+        }
+        n <<= 6 * padding as u32;
+
+        decoded.extend_from_slice(&n.to_be_bytes()[1..4 - padding]);
+    }
+
+    Some(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::base64::{decode, encode};
+
+    #[test]
+    fn encode_with_padding() {
+        assert_eq!(encode(b""), "");
+        assert_eq!(encode(b"f"), "Zg==");
+        assert_eq!(encode(b"fo"), "Zm8=");
+        assert_eq!(encode(b"foo"), "Zm9v");
+        assert_eq!(encode(b"foobar"), "Zm9vYmFy");
+    }
+
+    #[test]
+    fn decode_with_padding() {
+        assert_eq!(decode(""), Some(b"".to_vec()));
+        assert_eq!(decode("Zg=="), Some(b"f".to_vec()));
+        assert_eq!(decode("Zm8="), Some(b"fo".to_vec()));
+        assert_eq!(decode("Zm9vYmFy"), Some(b"foobar".to_vec()));
+    }
+
+    #[test]
+    fn decode_invalid() {
+        assert_eq!(decode("Zm9"), None);
+        assert_eq!(decode("Zm!v"), None);
+        assert_eq!(decode("Zg==Zm9v"), None);
+    }
+}
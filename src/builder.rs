@@ -0,0 +1,106 @@
+use crate::header::{find_header, Header};
+use crate::response::Response;
+use crate::status::Status;
+
+/// A builder for a http response, created with [Response::builder].
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct ResponseBuilder {
+    status: Option<Status>,
+    headers: Vec<Header>,
+    body: String,
+    content_type: Option<String>,
+}
+
+impl ResponseBuilder {
+    /// Create a new response builder for a `200 OK` response with no body.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the status of the http response.
+    pub fn status(self, status: Status) -> Self {
+        ResponseBuilder {
+            status: Some(status),
+            ..self
+        }
+    }
+
+    /// Add a header to the http response.
+    pub fn header(self, header: Header) -> Self {
+        let mut headers = self.headers;
+        headers.push(header);
+
+        ResponseBuilder { headers, ..self }
+    }
+
+    /// Set the body of the http response.
+    pub fn body(self, body: &str) -> Self {
+        ResponseBuilder {
+            body: body.to_string(),
+            ..self
+        }
+    }
+
+    /// Set the `Content-Type` header of the http response.
+    pub fn content_type(self, content_type: &str) -> Self {
+        ResponseBuilder {
+            content_type: Some(content_type.to_string()),
+            ..self
+        }
+    }
+
+    /// Build the http response. Sets the `Content-Length` header to the length of the body,
+    /// unless a `Content-Length` or `Transfer-Encoding` header has already been added.
+    pub fn build(self) -> Response {
+        let mut headers = Vec::new();
+
+        if let Some(content_type) = self.content_type {
+            headers.push(Header::new("Content-Type", &content_type));
+        }
+
+        if find_header(&self.headers, "Content-Length").is_none()
+            && find_header(&self.headers, "Transfer-Encoding").is_none()
+        {
+            let content_length = self.body.len() as u64;
+            headers.push(Header::new("Content-Length", &content_length.to_string()));
+        }
+
+        headers.extend(self.headers);
+
+        Response::body(&self.body)
+            .status(self.status.unwrap_or(Status::Ok))
+            .with_headers(headers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Header;
+    use crate::Response;
+    use crate::Status;
+
+    #[test]
+    fn build_json_response() {
+        let result = Response::builder()
+            .status(Status::NotFound)
+            .content_type("application/json")
+            .header(Header::new("Cache-Control", "no-cache"))
+            .body("{\"error\": \"not found\"}")
+            .build();
+        let expected = Response::json("{\"error\": \"not found\"}")
+            .status(Status::NotFound)
+            .header(Header::new("Cache-Control", "no-cache"));
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn build_keeps_explicit_framing() {
+        let result = Response::builder()
+            .header(Header::new("Transfer-Encoding", "chunked"))
+            .body("5\r\nhello\r\n0\r\n\r\n")
+            .build();
+
+        assert_eq!(result.content_length(), None);
+    }
+}
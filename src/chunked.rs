@@ -0,0 +1,46 @@
+/// Decode a body using chunked transfer encoding into the data it carries. Chunk extensions and
+/// trailers are discarded. Returns `None` if the body is not valid chunked data.
+pub(crate) fn decode_chunked(body: &[u8]) -> Option<Vec<u8>> {
+    let mut decoded = Vec::new();
+    let mut rest = body;
+
+    loop {
+        let line_end = rest.windows(2).position(|x| x == b"\r\n")?;
+        let line = std::str::from_utf8(&rest[..line_end]).ok()?;
+        let size = line.split(';').next()?.trim();
+        let size = usize::from_str_radix(size, 16).ok()?;
+        rest = &rest[line_end + 2..];
+
+        if size == 0 {
+            return Some(decoded);
+        }
+
+        let chunk = rest.get(..size)?;
+        if rest.get(size..size + 2)? != b"\r\n" {
+            return None;
+        }
+
+        decoded.extend_from_slice(chunk);
+        rest = &rest[size + 2..];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::chunked::decode_chunked;
+
+    #[test]
+    fn decode_chunks() {
+        let result = decode_chunked(b"5\r\nhello\r\n7;ext=1\r\n, world\r\n0\r\n\r\n");
+        let expected = Some(b"hello, world".to_vec());
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn decode_invalid_chunks() {
+        let result = decode_chunked(b"5\r\nhello world\r\n0\r\n\r\n");
+
+        assert_eq!(result, None);
+    }
+}
@@ -0,0 +1,41 @@
+//! Common content types, for use with [Response::content](crate::Response::content) and the
+//! `Content-Type` header.
+
+/// `application/json`
+pub const JSON: &str = "application/json";
+/// `text/html`
+pub const HTML: &str = "text/html";
+/// `text/plain`
+pub const PLAIN: &str = "text/plain";
+/// `text/css`
+pub const CSS: &str = "text/css";
+/// `text/javascript`
+pub const JS: &str = "text/javascript";
+/// `text/csv`
+pub const CSV: &str = "text/csv";
+/// `text/event-stream`
+pub const EVENT_STREAM: &str = "text/event-stream";
+/// `application/xml`
+pub const XML: &str = "application/xml";
+/// `application/pdf`
+pub const PDF: &str = "application/pdf";
+/// `application/wasm`
+pub const WASM: &str = "application/wasm";
+/// `application/octet-stream`
+pub const OCTET_STREAM: &str = "application/octet-stream";
+/// `image/png`
+pub const PNG: &str = "image/png";
+/// `image/jpeg`
+pub const JPEG: &str = "image/jpeg";
+/// `image/gif`
+pub const GIF: &str = "image/gif";
+/// `image/webp`
+pub const WEBP: &str = "image/webp";
+/// `image/svg+xml`
+pub const SVG: &str = "image/svg+xml";
+/// `image/x-icon`
+pub const ICO: &str = "image/x-icon";
+/// `font/woff`
+pub const WOFF: &str = "font/woff";
+/// `font/woff2`
+pub const WOFF2: &str = "font/woff2";
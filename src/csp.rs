@@ -0,0 +1,95 @@
+use std::fmt;
+
+/// A builder for the value of a `Content-Security-Policy` header.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Csp {
+    directives: Vec<(String, Vec<String>)>,
+}
+
+impl Csp {
+    /// Create a new empty content security policy.
+    pub fn new() -> Self {
+        Csp {
+            directives: Vec::new(),
+        }
+    }
+
+    /// Add a directive with the given sources to the policy.
+    pub fn directive(self, name: &str, sources: &[&str]) -> Self {
+        let mut directives = self.directives;
+        directives.push((
+            name.to_string(),
+            sources.iter().map(|s| s.to_string()).collect(),
+        ));
+
+        Csp { directives }
+    }
+
+    /// Add a `default-src` directive to the policy.
+    pub fn default_src(self, sources: &[&str]) -> Self {
+        self.directive("default-src", sources)
+    }
+
+    /// Add a `script-src` directive to the policy.
+    pub fn script_src(self, sources: &[&str]) -> Self {
+        self.directive("script-src", sources)
+    }
+
+    /// Add a `style-src` directive to the policy.
+    pub fn style_src(self, sources: &[&str]) -> Self {
+        self.directive("style-src", sources)
+    }
+
+    /// Add an `img-src` directive to the policy.
+    pub fn img_src(self, sources: &[&str]) -> Self {
+        self.directive("img-src", sources)
+    }
+
+    /// Add a `connect-src` directive to the policy.
+    pub fn connect_src(self, sources: &[&str]) -> Self {
+        self.directive("connect-src", sources)
+    }
+
+    /// Add a `font-src` directive to the policy.
+    pub fn font_src(self, sources: &[&str]) -> Self {
+        self.directive("font-src", sources)
+    }
+
+    /// Add a `frame-ancestors` directive to the policy.
+    pub fn frame_ancestors(self, sources: &[&str]) -> Self {
+        self.directive("frame-ancestors", sources)
+    }
+}
+
+impl fmt::Display for Csp {
+    /// Convert the `Csp` to a valid `Content-Security-Policy` header value.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let directives: Vec<String> = self
+            .directives
+            .iter()
+            .map(|(name, sources)| {
+                let mut directive = vec![name.as_str()];
+                directive.extend(sources.iter().map(|s| s.as_str()));
+                directive.join(" ")
+            })
+            .collect();
+
+        write!(f, "{}", directives.join("; "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Csp;
+
+    #[test]
+    fn string_representation() {
+        let result = Csp::new()
+            .default_src(&["'self'"])
+            .script_src(&["'self'", "https://cdn.example"])
+            .to_string();
+        let expected = "default-src 'self'; script-src 'self' https://cdn.example";
+
+        assert_eq!(result, expected);
+    }
+}
@@ -0,0 +1,185 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::error::{Error, ErrorType};
+
+const DAYS: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// Format a time as a http date in the IMF-fixdate format, e.g.
+/// `Sun, 06 Nov 1994 08:49:37 GMT`. Times before the unix epoch are formatted as the epoch.
+pub fn format_http_date(time: SystemTime) -> String {
+    let secs = match time.duration_since(UNIX_EPOCH) {
+        Ok(duration) => duration.as_secs(),
+        Err(_) => 0,
+    };
+
+    let days = secs / 86400;
+    let secs_of_day = secs % 86400;
+    let (year, month, day) = civil_from_days(days as i64);
+
+    format!(
+        "{}, {:02} {} {} {:02}:{:02}:{:02} GMT",
+        DAYS[(days % 7) as usize],
+        day,
+        MONTHS[(month - 1) as usize],
+        year,
+        secs_of_day / 3600,
+        secs_of_day % 3600 / 60,
+        secs_of_day % 60
+    )
+}
+
+/// Parse a http date into a time. Accepts the IMF-fixdate format, e.g.
+/// `Sun, 06 Nov 1994 08:49:37 GMT`, as well as the obsolete RFC 850 format, e.g.
+/// `Sunday, 06-Nov-94 08:49:37 GMT`, and asctime format, e.g. `Sun Nov  6 08:49:37 1994`.
+pub fn parse_http_date(s: &str) -> Result<SystemTime, Error> {
+    let parser_err = Error {
+        err_type: ErrorType::ParserError,
+        msg: "Invalid http date format".to_string(),
+    };
+
+    let parts: Vec<&str> = s.split_whitespace().collect();
+
+    let (day, month, year, time) = match parts.as_slice() {
+        [_, day, month, year, time, "GMT"] => (*day, *month, *year, *time),
+        [_, date, time, "GMT"] => {
+            let mut date_parts = date.split('-');
+            match (date_parts.next(), date_parts.next(), date_parts.next()) {
+                (Some(day), Some(month), Some(year)) => (day, month, year, *time),
+                _ => return Err(parser_err),
+            }
+        }
+        [_, month, day, time, year] => (*day, *month, *year, *time),
+        _ => return Err(parser_err),
+    };
+
+    let month = match MONTHS.iter().position(|m| *m == month) {
+        Some(i) => i as i64 + 1,
+        None => return Err(parser_err),
+    };
+
+    // Two digit years from the RFC 850 format are assumed to be between 1970 and 2069.
+    let two_digit_year = year.len() == 2;
+    let (day, year) = match (day.parse::<i64>(), year.parse::<i64>()) {
+        (Ok(day), Ok(year)) if two_digit_year && year < 70 => (day, year + 2000),
+        (Ok(day), Ok(year)) if two_digit_year => (day, year + 1900),
+        (Ok(day), Ok(year)) => (day, year),
+        _ => return Err(parser_err),
+    };
+
+    let mut time_parts = time.split(':').map(|x| x.parse::<i64>());
+    let (hours, minutes, seconds) = match (time_parts.next(), time_parts.next(), time_parts.next())
+    {
+        (Some(Ok(hours)), Some(Ok(minutes)), Some(Ok(seconds))) => (hours, minutes, seconds),
+        _ => return Err(parser_err),
+    };
+
+    if !(1..=9999).contains(&year)
+        || !(1..=31).contains(&day)
+        || !(0..24).contains(&hours)
+        || !(0..60).contains(&minutes)
+        || !(0..=60).contains(&seconds)
+    {
+        return Err(parser_err);
+    }
+
+    let secs = days_from_civil(year, month, day) * 86400 + hours * 3600 + minutes * 60 + seconds;
+
+    match u64::try_from(secs) {
+        Ok(secs) => Ok(UNIX_EPOCH + Duration::from_secs(secs)),
+        Err(_) => Err(parser_err),
+    }
+}
+
+// Convert a (year, month, day) date in the proleptic gregorian calendar to a number of days since
+// the unix epoch.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = if month > 2 { month - 3 } else { month + 9 };
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+
+    era * 146097 + doe - 719468
+}
+
+// Convert a number of days since the unix epoch to a (year, month, day) date in the proleptic
+// gregorian calendar.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use crate::date::{format_http_date, parse_http_date};
+
+    #[test]
+    fn format_date() {
+        let result = format_http_date(UNIX_EPOCH + Duration::from_secs(784111777));
+        let expected = "Sun, 06 Nov 1994 08:49:37 GMT";
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn parse_imf_fixdate() {
+        let result = parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT");
+        let expected = Ok(UNIX_EPOCH + Duration::from_secs(784111777));
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn parse_rfc850_date() {
+        let result = parse_http_date("Sunday, 06-Nov-94 08:49:37 GMT");
+        let expected = Ok(UNIX_EPOCH + Duration::from_secs(784111777));
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn parse_asctime_date() {
+        let result = parse_http_date("Sun Nov  6 08:49:37 1994");
+        let expected = Ok(UNIX_EPOCH + Duration::from_secs(784111777));
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn round_trip_date() {
+        let time = UNIX_EPOCH + Duration::from_secs(1709251200);
+        let result = parse_http_date(&format_http_date(time));
+
+        assert_eq!(format_http_date(time), "Fri, 01 Mar 2024 00:00:00 GMT");
+        assert_eq!(result, Ok(time));
+    }
+
+    #[test]
+    fn parse_invalid_date() {
+        let result = parse_http_date("yesterday at noon");
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn parse_date_with_overlong_year() {
+        let result = parse_http_date("Sun, 06 Nov 999999999999999 08:49:37 GMT");
+
+        assert!(result.is_err());
+    }
+}
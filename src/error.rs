@@ -1,8 +1,13 @@
+use std::io;
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum ErrorType {
     ParserError,
     InvalidMethod,
     InvalidCode,
+    InvalidReason,
+    InvalidHeader,
+    IoError,
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -10,3 +15,12 @@ pub struct Error {
     pub err_type: ErrorType,
     pub msg: String,
 }
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error {
+            err_type: ErrorType::IoError,
+            msg: err.to_string(),
+        }
+    }
+}
@@ -0,0 +1,254 @@
+const WINDOW_SIZE: usize = 32768;
+const HASH_SIZE: usize = 1 << 15;
+const MAX_CHAIN: usize = 64;
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+const NONE: usize = usize::MAX;
+
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+
+/// Compress data into the gzip format, as defined in RFC 1952. The data is compressed with a
+/// single deflate block using the fixed huffman codes, which needs no code tables in the output
+/// and works well for the small to medium bodies of http responses.
+pub(crate) fn gzip(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x1f, 0x8b, 8, 0, 0, 0, 0, 0, 0, 0xff];
+    out.extend_from_slice(&deflate(data));
+    out.extend_from_slice(&crc32(data).to_le_bytes());
+    out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+
+    out
+}
+
+// Compress data into a single final deflate block with the fixed huffman codes, as defined in
+// RFC 1951. Repeated sequences are found with a hash chain over the previous 32 KiB.
+fn deflate(data: &[u8]) -> Vec<u8> {
+    let mut bits = BitWriter::new();
+    bits.write(1, 1);
+    bits.write(1, 2);
+
+    let mut head = vec![NONE; HASH_SIZE];
+    let mut prev = vec![NONE; data.len()];
+    let mut i = 0;
+
+    while i < data.len() {
+        let (length, distance) = longest_match(data, i, &head, &prev);
+
+        let end = if length >= MIN_MATCH {
+            write_match(&mut bits, length, distance);
+            i + length
+        } else {
+            write_literal(&mut bits, data[i] as u16);
+            i + 1
+        };
+
+        while i < end {
+            if i + MIN_MATCH <= data.len() {
+                let hash = hash(&data[i..]);
+                prev[i] = head[hash];
+                head[hash] = i;
+            }
+            i += 1;
+        }
+    }
+
+    write_literal(&mut bits, 256);
+    bits.finish()
+}
+
+// Find the longest earlier occurrence of the data at `pos`, returning its length and distance.
+fn longest_match(data: &[u8], pos: usize, head: &[usize], prev: &[usize]) -> (usize, usize) {
+    if pos + MIN_MATCH > data.len() {
+        return (0, 0);
+    }
+
+    let max_length = MAX_MATCH.min(data.len() - pos);
+    let mut best = (0, 0);
+    let mut candidate = head[hash(&data[pos..])];
+    let mut chain = 0;
+
+    while candidate != NONE && pos - candidate <= WINDOW_SIZE && chain < MAX_CHAIN {
+        let length = data[candidate..]
+            .iter()
+            .zip(&data[pos..pos + max_length])
+            .take_while(|(a, b)| a == b)
+            .count();
+
+        if length > best.0 {
+            best = (length, pos - candidate);
+            if length == max_length {
+                break;
+            }
+        }
+
+        candidate = prev[candidate];
+        chain += 1;
+    }
+
+    best
+}
+
+fn hash(bytes: &[u8]) -> usize {
+    let n = (bytes[0] as usize) << 16 | (bytes[1] as usize) << 8 | bytes[2] as usize;
+
+    (n.wrapping_mul(2654435761) >> 7) % HASH_SIZE
+}
+
+// Write a literal byte or the end of block marker with its fixed huffman code.
+fn write_literal(bits: &mut BitWriter, value: u16) {
+    match value {
+        0..=143 => bits.write_code(0x30 + value as u32, 8),
+        144..=255 => bits.write_code(0x190 + (value as u32 - 144), 9),
+        256..=279 => bits.write_code(value as u32 - 256, 7),
+        _ => bits.write_code(0xc0 + (value as u32 - 280), 8),
+    }
+}
+
+fn write_match(bits: &mut BitWriter, length: usize, distance: usize) {
+    let code = LENGTH_BASE
+        .iter()
+        .rposition(|base| *base as usize <= length)
+        .unwrap_or(0);
+    write_literal(bits, 257 + code as u16);
+    bits.write(
+        (length - LENGTH_BASE[code] as usize) as u32,
+        LENGTH_EXTRA[code],
+    );
+
+    let code = DISTANCE_BASE
+        .iter()
+        .rposition(|base| *base as usize <= distance)
+        .unwrap_or(0);
+    bits.write_code(code as u32, 5);
+    bits.write(
+        (distance - DISTANCE_BASE[code] as usize) as u32,
+        DISTANCE_EXTRA[code],
+    );
+}
+
+// Compute the CRC-32 checksum used by gzip.
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xffffffffu32;
+
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb88320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+
+    !crc
+}
+
+// Write bits least significant bit first, as deflate expects.
+struct BitWriter {
+    out: Vec<u8>,
+    buffer: u32,
+    count: u8,
+}
+
+impl BitWriter {
+    fn new() -> Self {
+        BitWriter {
+            out: Vec::new(),
+            buffer: 0,
+            count: 0,
+        }
+    }
+
+    fn write(&mut self, value: u32, count: u8) {
+        for i in 0..count {
+            self.buffer |= (value >> i & 1) << self.count;
+            self.count += 1;
+
+            if self.count == 8 {
+                self.out.push(self.buffer as u8);
+                self.buffer = 0;
+                self.count = 0;
+            }
+        }
+    }
+
+    // Write a huffman code, which is stored most significant bit first.
+    fn write_code(&mut self, code: u32, count: u8) {
+        for i in (0..count).rev() {
+            self.write(code >> i & 1, 1);
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.count > 0 {
+            self.out.push(self.buffer as u8);
+        }
+
+        self.out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::gzip::{crc32, gzip};
+
+    #[test]
+    fn crc32_checksum() {
+        let result = crc32(b"123456789");
+        let expected = 0xcbf43926;
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn gzip_empty() {
+        let result = gzip(b"");
+        let expected = vec![
+            0x1f, 0x8b, 8, 0, 0, 0, 0, 0, 0, 0xff, 3, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        ];
+
+        assert_eq!(result, expected);
+    }
+
+    // The expected bytes were checked by decompressing them with Python's gzip module.
+    #[test]
+    fn gzip_fixed_input() {
+        let result = gzip(
+            b"The quick brown fox jumps over the lazy dog. The quick brown fox jumps over the lazy dog!",
+        );
+        let expected = vec![
+            31, 139, 8, 0, 0, 0, 0, 0, 0, 255, 11, 201, 72, 85, 40, 44, 205, 76, 206, 86, 72, 42,
+            202, 47, 207, 83, 72, 203, 175, 80, 200, 42, 205, 45, 40, 86, 200, 47, 75, 45, 82, 40,
+            1, 74, 231, 36, 86, 85, 42, 164, 228, 167, 235, 41, 144, 160, 88, 17, 0, 54, 23, 86,
+            10, 89, 0, 0, 0,
+        ];
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn gzip_repeated_data_is_smaller() {
+        let data = "hello world, ".repeat(100);
+        let result = gzip(data.as_bytes());
+
+        assert!(result.len() < data.len() / 4);
+        assert_eq!(
+            result[result.len() - 4..],
+            (data.len() as u32).to_le_bytes()
+        );
+    }
+}
@@ -1,3 +1,7 @@
+use std::fmt;
+
+use crate::error::{Error, ErrorType};
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct Header {
     pub key: String,
@@ -8,16 +12,66 @@ impl Header {
     /// Create a new http header from a key-value pair.
     pub fn new(key: &str, value: &str) -> Self {
         Header {
-           key: key.to_string(),
-           value: value.to_string(),
+            key: key.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    /// Create a new http header with several values, which are joined into a single comma
+    /// separated value, e.g. `Accept: text/html, application/json`.
+    pub fn multi(key: &str, values: &[&str]) -> Self {
+        Self::new(key, &values.join(", "))
+    }
+
+    /// Get the values of a comma separated http header, with surrounding whitespace removed.
+    pub fn values(&self) -> Vec<&str> {
+        self.value.split(',').map(|x| x.trim()).collect()
+    }
+
+    /// Check that the header is valid to send in a http message. The key must be a non-empty
+    /// token, and the value must not contain line breaks or null characters, since these could
+    /// be used to inject additional headers.
+    pub fn validate(&self) -> Result<(), Error> {
+        let header_err = Error {
+            err_type: ErrorType::InvalidHeader,
+            msg: "Invalid header name or value".to_string(),
+        };
+
+        if self.key.is_empty() || !self.key.bytes().all(is_token_char) {
+            return Err(header_err);
         }
+
+        if self.value.contains(['\r', '\n', '\0']) {
+            return Err(header_err);
+        }
+
+        Ok(())
+    }
+}
+
+/// Find the value of the first header with the given key, ignoring case.
+pub(crate) fn find_header<'a>(headers: &'a [Header], key: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|h| h.key.eq_ignore_ascii_case(key))
+        .map(|h| h.value.as_str())
+}
+
+fn is_token_char(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&byte)
+}
+
+impl From<(&str, &str)> for Header {
+    /// Create a new http header from a `(key, value)` tuple.
+    fn from((key, value): (&str, &str)) -> Self {
+        Header::new(key, value)
     }
 }
 
-impl ToString for Header {
+impl fmt::Display for Header {
     /// Convert the `Header` to a valid http plaintext representation.
-    fn to_string(&self) -> String {
-        format!("{}: {}", self.key, self.value)
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.key, self.value)
     }
 }
 
@@ -43,4 +97,40 @@ mod tests {
 
         assert_eq!(result, expected);
     }
+
+    #[test]
+    fn validate_header() {
+        let result = Header::new("Content-Type", "text/html").validate();
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn validate_header_with_newline_in_value() {
+        let result = Header::new("Set-Cookie", "a=1\r\nLocation: /evil").validate();
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn validate_header_with_invalid_key() {
+        let result = Header::new("Content Type", "text/html").validate();
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn multi_value_header() {
+        let result = Header::multi("Accept", &["text/html", "application/json"]);
+
+        assert_eq!(result.to_string(), "Accept: text/html, application/json");
+        assert_eq!(result.values(), vec!["text/html", "application/json"]);
+    }
+
+    #[test]
+    fn header_from_tuple() {
+        let result: Header = ("Content-Type", "text/html").into();
+
+        assert_eq!(result.to_string(), "Content-Type: text/html");
+    }
 }
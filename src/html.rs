@@ -0,0 +1,67 @@
+/// Escape the characters which have a special meaning in html, so that text can be safely
+/// included in a html document.
+pub(crate) fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Replace each `{{key}}` placeholder in a template with the html escaped value for that key.
+/// Placeholders whose key is not in `vars` are left in the output as they are.
+pub(crate) fn render_template(template: &str, vars: &[(&str, &str)]) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let end = match rest.find("}}") {
+            Some(end) => end,
+            None => break,
+        };
+
+        let placeholder = &rest[..end + 2];
+        match vars.iter().find(|(key, _)| *key == &placeholder[2..end]) {
+            Some((_, value)) => rendered.push_str(&escape_html(value)),
+            None => rendered.push_str(placeholder),
+        }
+
+        rest = &rest[end + 2..];
+    }
+
+    rendered.push_str(rest);
+    rendered
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::html::{escape_html, render_template};
+
+    #[test]
+    fn escape_special_characters() {
+        let result = escape_html("<a href=\"/\">Tom & Jerry's</a>");
+        let expected = "&lt;a href=&quot;/&quot;&gt;Tom &amp; Jerry&#39;s&lt;/a&gt;";
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn render_unclosed_placeholder() {
+        let result = render_template("Hello, {{name", &[("name", "world")]);
+        let expected = "Hello, {{name";
+
+        assert_eq!(result, expected);
+    }
+}
@@ -9,6 +9,11 @@
 //! library such as [tokio](https://docs.rs/tokio/latest/tokio/) or
 //! [async-std](https://docs.rs/async-std/latest/async_std/) to build a custom HTTP server.
 //!
+//! The optional `serde` feature adds `Response::json_pretty`, which builds a pretty printed json
+//! response from any serializable value.
+//! The optional `digest` feature adds `Response::content_digest`, which sets a SHA-256 `Digest`
+//! header for the body.
+//!
 //! This library is intended to abstract away the details of dealing with HTTP, without removing
 //! the need to understand how HTTP works at a high level. For example there are a few helper
 //! methods which will automatically set relevant headers. But for the most part, it is up to the
@@ -49,7 +54,7 @@
 //! Use `nanohttp` to build a custom TCP server using only the
 //! [async-std](https://docs.rs/async-std/latest/async_std/) crate as a dependency.
 //!
-//! ```
+//! ```no_run
 //! use std::str::from_utf8;
 //!
 //! use async_std::io::{ReadExt, WriteExt};
@@ -104,16 +109,38 @@
 //! }
 //! ```
 
+mod base64;
+mod builder;
+mod chunked;
+pub mod content_type;
+mod csp;
+mod date;
 mod error;
+mod gzip;
 mod header;
+mod html;
 mod method;
+mod mime;
+mod negotiate;
 mod request;
 mod response;
+mod scheme;
+mod sse;
 mod status;
+mod url;
+mod websocket;
 
+pub use builder::ResponseBuilder;
+pub use csp::Csp;
+pub use date::{format_http_date, parse_http_date};
 pub use error::{Error, ErrorType};
 pub use header::Header;
 pub use method::Method;
-pub use request::{Path, Request};
-pub use response::Response;
+pub use mime::parse_content_type;
+pub use negotiate::{negotiate_language, parse_accept_encoding, parse_accept_language};
+pub use request::{Path, Request, TargetForm};
+pub use response::{ParseLimits, Response};
+pub use scheme::Scheme;
+pub use sse::sse_event;
 pub use status::Status;
+pub use websocket::websocket_accept;
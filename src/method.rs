@@ -1,3 +1,5 @@
+use std::fmt;
+
 use crate::error::{Error, ErrorType};
 
 #[derive(Debug, PartialEq, Clone)]
@@ -7,6 +9,9 @@ pub enum Method {
     POST,
     PUT,
     DELETE,
+    OPTIONS,
+    CONNECT,
+    TRACE,
 }
 
 impl Method {
@@ -17,33 +22,52 @@ impl Method {
             msg: "Invalid or unsupported http method".to_string(),
         };
 
+        let uppercase = from.to_uppercase();
+
         match from {
             "HEAD" => Ok(Method::HEAD),
             "GET" => Ok(Method::GET),
             "POST" => Ok(Method::POST),
             "PUT" => Ok(Method::PUT),
             "DELETE" => Ok(Method::DELETE),
+            "OPTIONS" => Ok(Method::OPTIONS),
+            "CONNECT" => Ok(Method::CONNECT),
+            "TRACE" => Ok(Method::TRACE),
+            _ if from != uppercase && Self::from_string(&uppercase).is_ok() => Err(Error {
+                err_type: ErrorType::InvalidMethod,
+                msg: format!("Http methods must be uppercase, use {}", uppercase),
+            }),
             _ => Err(method_err),
         }
     }
+
+    /// Create a new `Method` from a string representation, ignoring surrounding whitespace and
+    /// case, e.g. ` get ` is parsed as `GET`. Use [Method::from_string] for strict parsing.
+    pub fn from_string_lenient(from: &str) -> Result<Self, Error> {
+        Self::from_string(&from.trim().to_uppercase())
+    }
 }
 
-impl ToString for Method {
-    
-    fn to_string(&self) -> String {
-        match self {
+impl fmt::Display for Method {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let method = match self {
             Self::HEAD => "HEAD",
             Self::GET => "GET",
             Self::POST => "POST",
             Self::PUT => "PUT",
-            Self::DELETE => "DELETE"
-        }.to_string()
+            Self::DELETE => "DELETE",
+            Self::OPTIONS => "OPTIONS",
+            Self::CONNECT => "CONNECT",
+            Self::TRACE => "TRACE",
+        };
+
+        write!(f, "{}", method)
     }
-    
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::ErrorType;
     use crate::Method;
 
     #[test]
@@ -60,4 +84,22 @@ mod tests {
 
         assert!(result.is_err());
     }
+
+    #[test]
+    fn method_from_lowercase_string() {
+        let result = Method::from_string("get").unwrap_err();
+        let expected = "Http methods must be uppercase, use GET";
+
+        assert_eq!(result.err_type, ErrorType::InvalidMethod);
+        assert_eq!(result.msg, expected);
+    }
+
+    #[test]
+    fn method_from_lenient_string() {
+        let result = Method::from_string_lenient(" get ");
+        let expected = Ok(Method::GET);
+
+        assert_eq!(result, expected);
+        assert!(Method::from_string(" get ").is_err());
+    }
 }
@@ -0,0 +1,109 @@
+use crate::content_type;
+
+/// Guess the MIME type of a file from the extension of its path. Falls back to
+/// `application/octet-stream` when the extension is missing or unknown.
+pub(crate) fn guess_mime(path: &str) -> &'static str {
+    let extension = match path.rsplit_once('.') {
+        Some((_, extension)) if !extension.contains('/') => extension.to_lowercase(),
+        _ => return content_type::OCTET_STREAM,
+    };
+
+    match extension.as_str() {
+        "html" | "htm" => content_type::HTML,
+        "css" => content_type::CSS,
+        "js" | "mjs" => content_type::JS,
+        "json" => content_type::JSON,
+        "txt" => content_type::PLAIN,
+        "csv" => content_type::CSV,
+        "xml" => content_type::XML,
+        "svg" => content_type::SVG,
+        "png" => content_type::PNG,
+        "jpg" | "jpeg" => content_type::JPEG,
+        "gif" => content_type::GIF,
+        "webp" => content_type::WEBP,
+        "ico" => content_type::ICO,
+        "pdf" => content_type::PDF,
+        "wasm" => content_type::WASM,
+        "woff" => content_type::WOFF,
+        "woff2" => content_type::WOFF2,
+        _ => content_type::OCTET_STREAM,
+    }
+}
+
+/// Parse a `Content-Type` header value into the lowercased media type and its parameters, e.g.
+/// `text/html; charset=utf-8` becomes `("text/html", [("charset", "utf-8")])`. Parameter names
+/// are lowercased and quoted parameter values are unquoted.
+pub fn parse_content_type(value: &str) -> (String, Vec<(String, String)>) {
+    let mut parts = value.split(';');
+
+    let media_type = parts.next().unwrap_or_default().trim().to_lowercase();
+
+    let params = parts
+        .filter_map(|param| {
+            let (key, value) = param.split_once('=')?;
+            let value = value.trim();
+            let value = match value.strip_prefix('"').and_then(|x| x.strip_suffix('"')) {
+                Some(unquoted) => unquoted,
+                None => value,
+            };
+
+            Some((key.trim().to_lowercase(), value.to_string()))
+        })
+        .collect();
+
+    (media_type, params)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mime::{guess_mime, parse_content_type};
+
+    #[test]
+    fn guess_known_extension() {
+        let result = guess_mime("static/index.HTML");
+        let expected = "text/html";
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn guess_unknown_extension() {
+        let result = guess_mime("static.d/README");
+        let expected = "application/octet-stream";
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn parse_content_type_with_charset() {
+        let result = parse_content_type("Text/HTML; Charset=utf-8");
+        let expected = (
+            "text/html".to_string(),
+            vec![("charset".to_string(), "utf-8".to_string())],
+        );
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn parse_content_type_with_boundary() {
+        let result = parse_content_type("multipart/form-data; boundary=abc");
+        let expected = (
+            "multipart/form-data".to_string(),
+            vec![("boundary".to_string(), "abc".to_string())],
+        );
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn parse_content_type_with_quoted_parameter() {
+        let result = parse_content_type("multipart/form-data; boundary=\"a b\"");
+        let expected = (
+            "multipart/form-data".to_string(),
+            vec![("boundary".to_string(), "a b".to_string())],
+        );
+
+        assert_eq!(result, expected);
+    }
+}
@@ -0,0 +1,169 @@
+/// Parse an `Accept-Language` header value into a list of language tags and their quality
+/// values, sorted from most to least preferred. Entries with a malformed quality value are
+/// skipped.
+pub fn parse_accept_language(value: &str) -> Vec<(String, f32)> {
+    parse_weighted(value)
+}
+
+/// Pick the best language from `available` for an `Accept-Language` header value. A language
+/// range matches an available language exactly or as a prefix, so `en` matches `en-US`, and `*`
+/// matches any language. Languages with a quality of `0` are never picked.
+pub fn negotiate_language(accepted: &str, available: &[&str]) -> Option<String> {
+    let accepted = parse_accept_language(accepted);
+
+    let is_rejected = |lang: &str| {
+        accepted
+            .iter()
+            .any(|(tag, q)| *q == 0.0 && tag.eq_ignore_ascii_case(lang))
+    };
+
+    for (tag, q) in accepted.iter() {
+        if *q == 0.0 {
+            continue;
+        }
+
+        let found = available.iter().find(|lang| {
+            !is_rejected(lang)
+                && (tag == "*"
+                    || tag.eq_ignore_ascii_case(lang)
+                    || is_prefix(tag, lang)
+                    || is_prefix(lang, tag))
+        });
+
+        if let Some(lang) = found {
+            return Some(lang.to_string());
+        }
+    }
+
+    None
+}
+
+/// Parse an `Accept-Encoding` header value into a list of content codings and their quality
+/// values, sorted from most to least preferred. Codings with a quality of `0` are not acceptable
+/// and are left out. The `identity` coding is acceptable unless it is excluded explicitly or with
+/// `*;q=0`, so when it is not listed it is added as the least preferred coding, with a quality of
+/// `0.001`.
+pub fn parse_accept_encoding(value: &str) -> Vec<(String, f32)> {
+    let weighted = parse_weighted(value);
+
+    let listed = |name: &str| {
+        weighted
+            .iter()
+            .find(|(coding, _)| coding.eq_ignore_ascii_case(name))
+    };
+    let add_identity =
+        listed("identity").is_none() && !matches!(listed("*"), Some((_, q)) if *q == 0.0);
+
+    let mut accepted: Vec<(String, f32)> = weighted.into_iter().filter(|(_, q)| *q > 0.0).collect();
+
+    if add_identity {
+        accepted.push(("identity".to_string(), 0.001));
+    }
+
+    accepted
+}
+
+// Check whether a language tag is a prefix of another, e.g. `en` is a prefix of `en-US`.
+fn is_prefix(prefix: &str, tag: &str) -> bool {
+    tag.len() > prefix.len()
+        && tag.as_bytes()[prefix.len()] == b'-'
+        && tag[..prefix.len()].eq_ignore_ascii_case(prefix)
+}
+
+// Parse a comma separated list of values with optional `q` parameters, sorted by quality.
+fn parse_weighted(value: &str) -> Vec<(String, f32)> {
+    let mut weighted: Vec<(String, f32)> = value
+        .split(',')
+        .filter_map(|item| {
+            let mut params = item.split(';').map(|x| x.trim());
+
+            let name = match params.next() {
+                Some(name) if !name.is_empty() => name,
+                _ => return None,
+            };
+
+            let mut quality = 1.0;
+            for param in params {
+                if let Some((key, value)) = param.split_once('=') {
+                    if key.trim().eq_ignore_ascii_case("q") {
+                        quality = match value.trim().parse::<f32>() {
+                            Ok(q) if (0.0..=1.0).contains(&q) => q,
+                            _ => return None,
+                        };
+                    }
+                }
+            }
+
+            Some((name.to_string(), quality))
+        })
+        .collect();
+
+    weighted.sort_by(|a, b| b.1.total_cmp(&a.1));
+    weighted
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::negotiate::{negotiate_language, parse_accept_encoding, parse_accept_language};
+
+    #[test]
+    fn parse_language_sorted_by_quality() {
+        let result = parse_accept_language("fr;q=0.5, en-US, de;q=0.8, *;q=0.1");
+        let expected = vec![
+            ("en-US".to_string(), 1.0),
+            ("de".to_string(), 0.8),
+            ("fr".to_string(), 0.5),
+            ("*".to_string(), 0.1),
+        ];
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn negotiate_direct_match() {
+        let result = negotiate_language("fr;q=0.5, de", &["en", "fr", "de"]);
+        let expected = Some("de".to_string());
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn negotiate_prefix_match() {
+        let result = negotiate_language("en, fr;q=0.5", &["fr", "en-US"]);
+        let expected = Some("en-US".to_string());
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn negotiate_wildcard() {
+        let result = negotiate_language("de, *;q=0.1, en;q=0", &["en", "fr"]);
+        let expected = Some("fr".to_string());
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn negotiate_no_match() {
+        let result = negotiate_language("de", &["en", "fr"]);
+
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn parse_encoding_excludes_q_zero() {
+        let result = parse_accept_encoding("gzip;q=1.0, br;q=0");
+        let expected = vec![("gzip".to_string(), 1.0), ("identity".to_string(), 0.001)];
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn parse_encoding_excludes_identity() {
+        let result = parse_accept_encoding("br;q=0.5, gzip, *;q=0");
+        let expected = vec![("gzip".to_string(), 1.0), ("br".to_string(), 0.5)];
+
+        assert_eq!(result, expected);
+        assert_eq!(parse_accept_encoding("identity;q=0"), vec![]);
+    }
+}
@@ -1,6 +1,10 @@
+use std::fmt;
+
+use crate::base64;
 use crate::error::{Error, ErrorType};
-use crate::header::Header;
+use crate::header::{find_header, Header};
 use crate::method::Method;
+use crate::url::percent_decode;
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct Query {
@@ -46,7 +50,7 @@ impl Path {
 
         let query: Vec<Query> = query_string
             .split("&")
-            .flat_map(|x| Self::parse_query(x))
+            .flat_map(Self::parse_query)
             .collect();
 
         Path {
@@ -68,10 +72,7 @@ impl Path {
             None => return Err(parser_err),
         };
 
-        let value = match parts.next() {
-            Some(value) => value,
-            None => "",
-        };
+        let value = parts.next().unwrap_or_default();
 
         Ok(Query {
             key: key.to_string(),
@@ -80,29 +81,241 @@ impl Path {
     }
 }
 
-#[derive(Debug, PartialEq)]
+/// The form of the request-target in a http request line, as defined in RFC 7230 section 5.3.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum TargetForm {
+    /// An absolute path, e.g. `/index.html?page=1`.
+    Origin,
+    /// An absolute URI, e.g. `http://example.com/index.html`, used for requests to a proxy.
+    Absolute,
+    /// A host and port, e.g. `example.com:443`, used with `CONNECT`.
+    Authority,
+    /// A single asterisk `*`, used with `OPTIONS` for the server as a whole.
+    Asterisk,
+}
+
+#[derive(Debug, PartialEq, Clone)]
 pub struct Request {
     pub method: Method,
     pub path: Path,
     pub scheme: String,
     pub version: String,
     pub headers: Vec<Header>,
-    pub body: String,
+    pub body: Vec<u8>,
 }
 
 impl Request {
     /// Parse a http plaintext request into a `Request` object.
     pub fn from_string(buffer: &str) -> Result<Self, Error> {
-        Self::parse(buffer)
+        Self::parse_bytes(buffer.as_bytes())
+    }
+
+    /// Parse a http plaintext request into a `Request` object, rejecting requests which could be
+    /// used for request smuggling. A request with more than one `Host` header is an error.
+    pub fn parse_strict(buffer: &str) -> Result<Self, Error> {
+        let request = Self::parse_bytes(buffer.as_bytes())?;
+
+        let hosts = request
+            .headers
+            .iter()
+            .filter(|h| h.key.eq_ignore_ascii_case("Host"))
+            .count();
+
+        if hosts > 1 {
+            return Err(Error {
+                err_type: ErrorType::ParserError,
+                msg: "Multiple Host headers are set".to_string(),
+            });
+        }
+
+        Ok(request)
+    }
+
+    /// Get the method of the http request.
+    pub fn method(&self) -> &Method {
+        &self.method
+    }
+
+    /// Get the path of the http request.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Get the http version of the request, e.g. `1.1`.
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// Get the form of the request-target of the http request.
+    pub fn target_form(&self) -> TargetForm {
+        let target = self.path.uri.as_str();
+
+        if target == "*" {
+            TargetForm::Asterisk
+        } else if target.starts_with('/') {
+            TargetForm::Origin
+        } else if target.contains("://") {
+            TargetForm::Absolute
+        } else {
+            TargetForm::Authority
+        }
+    }
+
+    /// Get the method the http request should be handled as. A `POST` request with an
+    /// `X-HTTP-Method-Override` header is treated as the method named in the header, and any
+    /// other request is treated as its actual method.
+    pub fn effective_method(&self) -> Result<Method, Error> {
+        match (&self.method, self.find_header("X-HTTP-Method-Override")) {
+            (Method::POST, Some(method)) => Method::from_string(method.trim()),
+            (method, _) => Ok(method.clone()),
+        }
+    }
+
+    /// Check whether the http request has the given method and path. The path is compared
+    /// exactly, without the query string.
+    pub fn matches(&self, method: Method, path: &str) -> bool {
+        self.method == method && self.path.uri == path
+    }
+
+    /// Get the segments of the path of the http request, for routing. The path is split on `/`
+    /// before each segment is percent-decoded, so an encoded slash `%2F` stays within its
+    /// segment. Empty segments are dropped.
+    pub fn path_segments(&self) -> Vec<String> {
+        self.path
+            .uri
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .map(percent_decode)
+            .collect()
+    }
+
+    /// Get the path of the http request, without the query string, with a trailing slash added
+    /// if `trailing_slash` is true, or removed otherwise. The root path `/` is left as it is.
+    pub fn normalized_path(&self, trailing_slash: bool) -> String {
+        let path = self.path.uri.trim_end_matches('/');
+
+        if path.is_empty() {
+            "/".to_string()
+        } else if trailing_slash {
+            format!("{}/", path)
+        } else {
+            path.to_string()
+        }
+    }
+
+    /// Get the body of the http request. The body is bounded by the `Content-Length` header, and
+    /// is empty when the request declares neither a length nor chunked transfer encoding.
+    pub fn body(&self) -> &[u8] {
+        &self.body
+    }
+
+    /// Get the body of the http request as text. Returns `None` if the body is not valid UTF-8.
+    pub fn body_str(&self) -> Option<&str> {
+        std::str::from_utf8(&self.body).ok()
+    }
+
+    /// Check whether the client sent an `Expect: 100-continue` header, meaning it will wait for an
+    /// interim `100 Continue` response before sending the body.
+    pub fn expects_continue(&self) -> bool {
+        match self.find_header("Expect") {
+            Some(expect) => expect.trim().eq_ignore_ascii_case("100-continue"),
+            None => false,
+        }
+    }
+
+    /// Get the origin of the http request, e.g. `https://example.com`. Uses the `Origin` header
+    /// if it is present, and otherwise the origin of the `Referer` header.
+    pub fn origin(&self) -> Option<&str> {
+        if let Some(origin) = self.find_header("Origin") {
+            return Some(origin.trim());
+        }
+
+        let referer = self.find_header("Referer")?.trim();
+        let (_, rest) = referer.split_once("://")?;
+
+        match rest.find(['/', '?', '#']) {
+            Some(i) => Some(&referer[..referer.len() - rest.len() + i]),
+            None => Some(referer),
+        }
+    }
+
+    /// Check whether the origin of the http request has the given host, e.g. `example.com` or
+    /// `localhost:3333`. Returns false when the request has no origin.
+    pub fn is_same_origin(&self, host: &str) -> bool {
+        match self.origin().and_then(|origin| origin.split_once("://")) {
+            Some((_, authority)) => authority.eq_ignore_ascii_case(host),
+            None => false,
+        }
+    }
+
+    /// Get the cookies sent by the client in the `Cookie` header, as name and value pairs in the
+    /// order they were sent. Values are split on the first `=`, so they may contain `=`, and
+    /// cookies without a `=` are skipped.
+    pub fn cookies(&self) -> Vec<(String, String)> {
+        let cookie = match self.find_header("Cookie") {
+            Some(cookie) => cookie,
+            None => return Vec::new(),
+        };
+
+        cookie
+            .split(';')
+            .filter_map(|pair| pair.split_once('='))
+            .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+            .collect()
+    }
+
+    /// Get the token of an `Authorization: Bearer <token>` header. Returns `None` when the header
+    /// is missing or uses another scheme, e.g. `Basic`.
+    pub fn bearer_token(&self) -> Option<&str> {
+        let (scheme, token) = self.find_header("Authorization")?.trim().split_once(' ')?;
+        let token = token.trim();
+
+        if scheme.eq_ignore_ascii_case("Bearer") && !token.is_empty() {
+            Some(token)
+        } else {
+            None
+        }
+    }
+
+    /// Get the username and password of an `Authorization: Basic <credentials>` header. The
+    /// credentials are split on the first `:`, so the password may contain colons. Returns `None`
+    /// when the header is missing, uses another scheme, or is not valid base64 encoded UTF-8.
+    pub fn basic_auth(&self) -> Option<(String, String)> {
+        let (scheme, credentials) = self.find_header("Authorization")?.trim().split_once(' ')?;
+        if !scheme.eq_ignore_ascii_case("Basic") {
+            return None;
+        }
+
+        let credentials = String::from_utf8(base64::decode(credentials.trim())?).ok()?;
+        let (username, password) = credentials.split_once(':')?;
+
+        Some((username.to_string(), password.to_string()))
     }
-    
-    fn parse(buffer: &str) -> Result<Request, Error> {
+
+    fn find_header(&self, key: &str) -> Option<&str> {
+        find_header(&self.headers, key)
+    }
+
+    /// Parse a http request into a `Request` object. The request line and headers must be valid
+    /// UTF-8, but the body is kept as raw bytes, so binary bodies such as file uploads can be
+    /// parsed.
+    pub fn parse_bytes(buffer: &[u8]) -> Result<Self, Error> {
         let parser_err = Error {
             err_type: ErrorType::ParserError,
             msg: "Invalid request format".to_string(),
         };
 
-        let mut parts = buffer.split("\r\n");
+        let (head, rest) = match buffer.windows(4).position(|x| x == b"\r\n\r\n") {
+            Some(i) => (&buffer[..i], &buffer[i + 4..]),
+            None => (buffer, &[][..]),
+        };
+
+        let head = match std::str::from_utf8(head) {
+            Ok(head) => head,
+            Err(_) => return Err(parser_err),
+        };
+
+        let mut parts = head.split("\r\n");
 
         let start_line = match parts.next() {
             Some(start_line) => start_line,
@@ -112,12 +325,11 @@ impl Request {
         let (method, path, scheme, version) = Self::parse_start_line(start_line)?;
 
         let headers: Vec<Header> = parts
-            .clone()
-            .take_while(|x| x.to_owned() != "")
-            .flat_map(|x| Self::parse_header(x))
+            .take_while(|x| !x.is_empty())
+            .flat_map(Self::parse_header)
             .collect();
 
-        let body: String = parts.clone().skip_while(|x| x.to_owned() != "").collect();
+        let body = Self::parse_body(&headers, rest)?;
 
         Ok(Request {
             method,
@@ -129,6 +341,30 @@ impl Request {
         })
     }
 
+    fn parse_body(headers: &[Header], rest: &[u8]) -> Result<Vec<u8>, Error> {
+        let parser_err = Error {
+            err_type: ErrorType::ParserError,
+            msg: "Invalid or incomplete request body".to_string(),
+        };
+
+        if let Some(length) = find_header(headers, "Content-Length") {
+            let length = match length.trim().parse::<u64>().map(usize::try_from) {
+                Ok(Ok(length)) => length,
+                _ => return Err(parser_err),
+            };
+
+            return match rest.get(..length) {
+                Some(body) => Ok(body.to_vec()),
+                None => Err(parser_err),
+            };
+        }
+
+        match find_header(headers, "Transfer-Encoding") {
+            Some(encoding) if encoding.to_lowercase().contains("chunked") => Ok(rest.to_vec()),
+            _ => Ok(Vec::new()),
+        }
+    }
+
     fn parse_header(line: &str) -> Result<Header, Error> {
         let parser_err = Error {
             err_type: ErrorType::ParserError,
@@ -138,8 +374,8 @@ impl Request {
         let mut parts = line.split(": ");
 
         let key = match parts.next() {
-            Some(key) => key,
-            None => return Err(parser_err),
+            Some(key) if !key.is_empty() => key,
+            _ => return Err(parser_err),
         };
 
         let value = match parts.next() {
@@ -159,13 +395,13 @@ impl Request {
         let mut parts = line.split("/");
 
         let scheme = match parts.next() {
-            Some(scheme) => scheme,
-            None => return Err(parser_err),
+            Some(scheme) if !scheme.is_empty() => scheme,
+            _ => return Err(parser_err),
         };
 
         let version = match parts.next() {
-            Some(version) => version,
-            None => return Err(parser_err),
+            Some(version) if !version.is_empty() => version,
+            _ => return Err(parser_err),
         };
 
         Ok((scheme, version))
@@ -200,27 +436,37 @@ impl Request {
     }
 }
 
-impl ToString for Request {
+impl fmt::Display for Request {
     /// Convert the `Request` to a valid http plaintext request
-    fn to_string(&self) -> String {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let mut buf = String::new();
         buf.push_str(&self.method.to_string());
-        buf.push_str(" ");
+        buf.push(' ');
         buf.push_str(&self.path.uri);
-        buf.push_str("?");
-        let qrs: Vec<String> = self.path.query.iter().map(|q| format!("{}={}", q.key, q.value)).collect();
-        buf.push_str(&qrs.join("&"));
-        buf.push_str(" HTTP/");
+        if !self.path.query.is_empty() {
+            buf.push('?');
+            let qrs: Vec<String> = self
+                .path
+                .query
+                .iter()
+                .map(|q| format!("{}={}", q.key, q.value))
+                .collect();
+            buf.push_str(&qrs.join("&"));
+        }
+        buf.push(' ');
+        buf.push_str(&self.scheme);
+        buf.push('/');
         buf.push_str(&self.version);
         buf.push_str("\r\n");
-        let hds: Vec<String> = self.headers.iter().map(|h| h.to_string()).collect();
-        buf.push_str(&hds.join("\r\n"));
-        if buf.contains("Content-Length") {
-            buf.push_str("\r\n\r\n");
-            buf.push_str(&self.body);
+        for header in &self.headers {
+            buf.push_str(&header.to_string());
+            buf.push_str("\r\n");
         }
-        buf
-    }    
+        buf.push_str("\r\n");
+        buf.push_str(&String::from_utf8_lossy(&self.body));
+
+        write!(f, "{}", buf)
+    }
 }
 
 #[cfg(test)]
@@ -228,6 +474,7 @@ mod tests {
     use crate::Header;
     use crate::Method;
     use crate::Request;
+    use crate::TargetForm;
 
     #[test]
     fn parse_get_request() {
@@ -247,7 +494,7 @@ mod tests {
 
     #[test]
     fn parse_post_request() {
-        let req_string = "POST /hello-world HTTP/1.1\r\nHost: localhost:3333\r\nUser-Agent: curl/7.81.0\r\nAccept: */*\r\nContent-Type: application/json\r\nContent-Length: 18\r\n\r\n{ \"hello\": \"world\" }";
+        let req_string = "POST /hello-world HTTP/1.1\r\nHost: localhost:3333\r\nUser-Agent: curl/7.81.0\r\nAccept: */*\r\nContent-Type: application/json\r\nContent-Length: 20\r\n\r\n{ \"hello\": \"world\" }";
         let result = Request::from_string(req_string).unwrap();
 
         assert_eq!(result.method, Method::POST);
@@ -262,8 +509,8 @@ mod tests {
             result.headers[3],
             Header::new("Content-Type", "application/json")
         );
-        assert_eq!(result.headers[4], Header::new("Content-Length", "18"));
-        assert_eq!(result.body, "{ \"hello\": \"world\" }");
+        assert_eq!(result.headers[4], Header::new("Content-Length", "20"));
+        assert_eq!(result.body, b"{ \"hello\": \"world\" }");
     }
 
     #[test]
@@ -288,4 +535,292 @@ mod tests {
         assert_eq!(result.path.uri, "/hello-world");
         assert_eq!(result.path.query.len(), 0);
     }
+
+    #[test]
+    fn parse_body_bounded_by_content_length() {
+        let req_string =
+            "POST / HTTP/1.1\r\nHost: localhost:3333\r\nContent-Length: 5\r\n\r\nhello\r\nworld";
+        let result = Request::from_string(req_string).unwrap();
+
+        assert_eq!(result.body_str(), Some("hello"));
+    }
+
+    #[test]
+    fn parse_body_without_content_length() {
+        let req_string = "POST / HTTP/1.1\r\nHost: localhost:3333\r\n\r\nhello";
+        let result = Request::from_string(req_string).unwrap();
+
+        assert_eq!(result.body(), b"");
+    }
+
+    #[test]
+    fn parse_body_shorter_than_content_length() {
+        let req_string =
+            "POST / HTTP/1.1\r\nHost: localhost:3333\r\nContent-Length: 10\r\n\r\nhello";
+        let result = Request::from_string(req_string);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn request_expects_continue() {
+        let req_string = "PUT /upload HTTP/1.1\r\nHost: localhost:3333\r\nContent-Length: 0\r\nExpect: 100-continue\r\n\r\n";
+        let result = Request::from_string(req_string).unwrap();
+
+        assert!(result.expects_continue());
+    }
+
+    #[test]
+    fn request_does_not_expect_continue() {
+        let req_string =
+            "PUT /upload HTTP/1.1\r\nHost: localhost:3333\r\nContent-Length: 0\r\n\r\n";
+        let result = Request::from_string(req_string).unwrap();
+
+        assert!(!result.expects_continue());
+    }
+
+    #[test]
+    fn request_getters() {
+        let req_string = "GET /foo?x=1 HTTP/1.1\r\nHost: localhost:3333\r\n";
+        let result = Request::from_string(req_string).unwrap();
+
+        assert_eq!(result.method(), &Method::GET);
+        assert_eq!(result.path().uri, "/foo");
+        assert_eq!(result.version(), "1.1");
+    }
+
+    #[test]
+    fn request_matches_without_query() {
+        let req_string = "GET /foo?x=1 HTTP/1.1\r\nHost: localhost:3333\r\n";
+        let result = Request::from_string(req_string).unwrap();
+
+        assert!(result.matches(Method::GET, "/foo"));
+        assert!(!result.matches(Method::POST, "/foo"));
+        assert!(!result.matches(Method::GET, "/foo/bar"));
+    }
+
+    #[test]
+    fn normalize_path_remove_trailing_slash() {
+        let result = Request::from_string("GET /foo/ HTTP/1.1\r\n").unwrap();
+
+        assert_eq!(result.normalized_path(false), "/foo");
+    }
+
+    #[test]
+    fn normalize_path_add_trailing_slash() {
+        let result = Request::from_string("GET /foo HTTP/1.1\r\n").unwrap();
+
+        assert_eq!(result.normalized_path(true), "/foo/");
+    }
+
+    #[test]
+    fn normalize_root_path() {
+        let result = Request::from_string("GET / HTTP/1.1\r\n").unwrap();
+
+        assert_eq!(result.normalized_path(true), "/");
+        assert_eq!(result.normalized_path(false), "/");
+    }
+
+    #[test]
+    fn request_same_origin() {
+        let req_string =
+            "POST /login HTTP/1.1\r\nHost: example.com\r\nOrigin: https://example.com\r\n";
+        let result = Request::from_string(req_string).unwrap();
+
+        assert_eq!(result.origin(), Some("https://example.com"));
+        assert!(result.is_same_origin("example.com"));
+    }
+
+    #[test]
+    fn request_cross_origin() {
+        let req_string =
+            "POST /login HTTP/1.1\r\nHost: example.com\r\nOrigin: https://evil.com\r\n";
+        let result = Request::from_string(req_string).unwrap();
+
+        assert!(!result.is_same_origin("example.com"));
+    }
+
+    #[test]
+    fn request_origin_from_referer() {
+        let req_string = "POST /login HTTP/1.1\r\nHost: example.com\r\nReferer: https://example.com:8080/login?next=/\r\n";
+        let result = Request::from_string(req_string).unwrap();
+
+        assert_eq!(result.origin(), Some("https://example.com:8080"));
+        assert!(result.is_same_origin("example.com:8080"));
+    }
+
+    #[test]
+    fn request_without_origin() {
+        let req_string = "POST /login HTTP/1.1\r\nHost: example.com\r\n";
+        let result = Request::from_string(req_string).unwrap();
+
+        assert_eq!(result.origin(), None);
+        assert!(!result.is_same_origin("example.com"));
+    }
+
+    #[test]
+    fn request_with_method_override() {
+        let req_string =
+            "POST /items/1 HTTP/1.1\r\nHost: localhost:3333\r\nX-HTTP-Method-Override: DELETE\r\n";
+        let result = Request::from_string(req_string).unwrap();
+
+        assert_eq!(result.effective_method(), Ok(Method::DELETE));
+    }
+
+    #[test]
+    fn request_without_method_override() {
+        let req_string =
+            "GET /items/1 HTTP/1.1\r\nHost: localhost:3333\r\nX-HTTP-Method-Override: DELETE\r\n";
+        let result = Request::from_string(req_string).unwrap();
+
+        assert_eq!(result.effective_method(), Ok(Method::GET));
+    }
+
+    #[test]
+    fn request_with_invalid_method_override() {
+        let req_string =
+            "POST /items/1 HTTP/1.1\r\nHost: localhost:3333\r\nX-HTTP-Method-Override: PURGE\r\n";
+        let result = Request::from_string(req_string).unwrap();
+
+        assert!(result.effective_method().is_err());
+    }
+
+    #[test]
+    fn parse_strict_rejects_duplicate_host() {
+        let req_string = "GET / HTTP/1.1\r\nHost: example.com\r\nhost: evil.com\r\n";
+        let result = Request::parse_strict(req_string);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn parse_strict_accepts_single_host() {
+        let req_string = "GET / HTTP/1.1\r\nHost: example.com\r\n";
+        let result = Request::parse_strict(req_string);
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn origin_target_form() {
+        let result = Request::from_string("GET /index.html?page=1 HTTP/1.1\r\n").unwrap();
+
+        assert_eq!(result.target_form(), TargetForm::Origin);
+    }
+
+    #[test]
+    fn absolute_target_form() {
+        let result =
+            Request::from_string("GET http://example.com/index.html HTTP/1.1\r\n").unwrap();
+
+        assert_eq!(result.target_form(), TargetForm::Absolute);
+    }
+
+    #[test]
+    fn authority_target_form() {
+        let result = Request::from_string("CONNECT example.com:443 HTTP/1.1\r\n").unwrap();
+
+        assert_eq!(result.target_form(), TargetForm::Authority);
+    }
+
+    #[test]
+    fn asterisk_target_form() {
+        let result = Request::from_string("OPTIONS * HTTP/1.1\r\n").unwrap();
+
+        assert_eq!(result.target_form(), TargetForm::Asterisk);
+    }
+
+    #[test]
+    fn nested_path_segments() {
+        let req = Request::from_string("GET /users//42/posts/ HTTP/1.1\r\n").unwrap();
+        let result = req.path_segments();
+        let expected = vec!["users", "42", "posts"];
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn path_segments_with_encoded_slash() {
+        let req = Request::from_string("GET /files/a%2Fb/hello%20world HTTP/1.1\r\n").unwrap();
+        let result = req.path_segments();
+        let expected = vec!["files", "a/b", "hello world"];
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn bearer_token() {
+        let req =
+            Request::from_string("GET / HTTP/1.1\r\nAuthorization: Bearer  abc.def \r\n").unwrap();
+        let result = req.bearer_token();
+        let expected = Some("abc.def");
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn bearer_token_with_basic_auth() {
+        let req = Request::from_string("GET / HTTP/1.1\r\nAuthorization: Basic dXNlcjpwYXNz\r\n")
+            .unwrap();
+
+        assert_eq!(req.bearer_token(), None);
+    }
+
+    #[test]
+    fn bearer_token_without_header() {
+        let req = Request::from_string("GET / HTTP/1.1\r\n").unwrap();
+
+        assert_eq!(req.bearer_token(), None);
+    }
+
+    #[test]
+    fn basic_auth_credentials() {
+        let req =
+            Request::from_string("GET / HTTP/1.1\r\nAuthorization: Basic dXNlcjpwYTpzcw==\r\n")
+                .unwrap();
+        let result = req.basic_auth();
+        let expected = Some(("user".to_string(), "pa:ss".to_string()));
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn malformed_basic_auth_credentials() {
+        let req =
+            Request::from_string("GET / HTTP/1.1\r\nAuthorization: Basic not-base64!\r\n").unwrap();
+
+        assert_eq!(req.basic_auth(), None);
+    }
+
+    #[test]
+    fn parse_cookies() {
+        let req =
+            Request::from_string("GET / HTTP/1.1\r\nCookie: a=1; b=hello; token=x==\r\n").unwrap();
+        let result = req.cookies();
+        let expected = vec![
+            ("a".to_string(), "1".to_string()),
+            ("b".to_string(), "hello".to_string()),
+            ("token".to_string(), "x==".to_string()),
+        ];
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn request_to_string() {
+        let req_string = "POST /hello?name=foo HTTP/1.1\r\nHost: localhost:3333\r\nContent-Length: 5\r\n\r\nhello";
+        let result = Request::from_string(req_string).unwrap().to_string();
+
+        assert_eq!(result, req_string);
+    }
+
+    #[test]
+    fn parse_binary_body() {
+        let mut req_bytes = b"POST /upload HTTP/1.1\r\nContent-Length: 4\r\n\r\n".to_vec();
+        req_bytes.extend_from_slice(&[0xff, 0x00, 0xfe, 0x80]);
+        let result = Request::parse_bytes(&req_bytes).unwrap();
+
+        assert_eq!(result.body(), [0xff, 0x00, 0xfe, 0x80]);
+        assert_eq!(result.body_str(), None);
+    }
 }
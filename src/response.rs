@@ -1,24 +1,74 @@
-use crate::header::Header;
-use crate::status::Status;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::str::FromStr;
+
+use crate::builder::ResponseBuilder;
+use crate::chunked::decode_chunked;
+use crate::content_type;
+use crate::csp::Csp;
+use crate::date::format_http_date;
+use crate::error::{Error, ErrorType};
+use crate::gzip::gzip;
+use crate::header::{find_header, Header};
+use crate::html::{escape_html, render_template};
+use crate::method::Method;
+use crate::mime::guess_mime;
+use crate::request::Request;
+use crate::scheme::Scheme;
+use crate::status::{validate_reason, Status};
+use crate::url::percent_encode;
+use crate::websocket::websocket_accept;
+
+/// Limits applied when parsing a http response, to protect against pathological input.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ParseLimits {
+    /// The maximum length of the reason phrase in the status line, in bytes.
+    pub max_reason_len: usize,
+}
+
+impl Default for ParseLimits {
+    fn default() -> Self {
+        ParseLimits {
+            max_reason_len: 512,
+        }
+    }
+}
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct Response {
-    scheme: String,
+    scheme: Scheme,
     version: String,
     status: Status,
     headers: Vec<Header>,
-    content: String,
+    content: Vec<u8>,
+    reason: Option<String>,
 }
 
 impl Response {
-    /// Create a new http response with no body.
+    /// Create a new builder for a http response. The builder is an alternative to chaining the
+    /// methods of `Response`, and sets the `Content-Length` header automatically.
+    pub fn builder() -> ResponseBuilder {
+        ResponseBuilder::new()
+    }
+
+    /// Create a new http response with no body. The `Content-Length: 0` header is sent unless a
+    /// framing header is added, so the end of the response is unambiguous.
     pub fn empty() -> Self {
+        Self::with_protocol("HTTP", "1.1")
+    }
+
+    /// Create a new http response with no body, using the given scheme and version in the start
+    /// line instead of the default `HTTP/1.1`.
+    pub fn with_protocol(scheme: impl Into<Scheme>, version: &str) -> Self {
         Response {
-            scheme: "HTTP".to_string(),
-            version: "1.1".to_string(),
+            scheme: scheme.into(),
+            version: version.to_string(),
             status: Status::Ok,
             headers: Vec::new(),
-            content: String::new(),
+            content: Vec::new(),
+            reason: None,
         }
     }
 
@@ -26,35 +76,249 @@ impl Response {
     // `Content-Length` header.
     pub fn body(content: &str) -> Self {
         Response {
-            scheme: "HTTP".to_string(),
+            scheme: Scheme::Http,
             version: "1.1".to_string(),
             status: Status::Ok,
             headers: Vec::new(),
-            content: content.to_string(),
+            content: content.as_bytes().to_vec(),
+            reason: None,
         }
     }
 
     /// Create a new http response with a given body and content type. Sets the `Content-Type`
     /// header to the content type provided, and automatically sets the `Content-Length` header to
-    /// the length of the provided content.
+    /// the length of the provided content in bytes, not characters.
     pub fn content(content: &str, content_type: &str) -> Self {
-        let content_length = content.len();
+        let content_length = content.len() as u64;
 
         Self::body(content)
             .header(Header::new("Content-Type", content_type))
             .header(Header::new("Content-Length", &content_length.to_string()))
     }
 
+    /// Create a `json` http response by serializing a value as pretty printed json, e.g. for
+    /// debug endpoints. This method is the same as [Response::json] otherwise, and returns an
+    /// error if the value cannot be serialized. Requires the `serde` feature.
+    #[cfg(feature = "serde")]
+    pub fn json_pretty<T: serde::Serialize>(value: &T) -> Result<Self, Error> {
+        match serde_json::to_string_pretty(value) {
+            Ok(json) => Ok(Self::json(&json)),
+            Err(err) => Err(Error {
+                err_type: ErrorType::ParserError,
+                msg: err.to_string(),
+            }),
+        }
+    }
+
+    /// Create a new http response for streaming a body with the given headers. The response has
+    /// no body of its own, so it should be sent with [Response::write_body_from], and the headers
+    /// should describe how the body is framed, e.g. with a `Content-Length` header, or with
+    /// `Connection: close` for a body which ends when the connection closes.
+    pub fn streaming(headers: Vec<Header>) -> Self {
+        Self::empty().with_headers(headers)
+    }
+
+    /// Convert an informational (`1xx`) http response with the given status and headers to valid
+    /// http plaintext. Interim responses have no body, and are sent before the final response.
+    /// Returns an error if the status is not informational.
+    pub fn interim(status: Status, headers: Vec<Header>) -> Result<String, Error> {
+        if !(100..=199).contains(&status.code()) {
+            return Err(Error {
+                err_type: ErrorType::InvalidCode,
+                msg: "Interim responses must have an informational status".to_string(),
+            });
+        }
+
+        Ok(Self::empty()
+            .status(status)
+            .with_headers(headers)
+            .head_string())
+    }
+
+    /// Create a `101 Switching Protocols` http response, upgrading the connection to the given
+    /// protocol, e.g. `websocket`. Sets the `Connection: Upgrade` and `Upgrade` headers.
+    pub fn switching_protocols(protocol: &str) -> Self {
+        Self::with_protocol("HTTP", "1.1")
+            .status(Status::SwitchingProtocols)
+            .header(Header::new("Connection", "Upgrade"))
+            .header(Header::new("Upgrade", protocol))
+    }
+
+    /// Create a `101 Switching Protocols` http response accepting a websocket handshake. Sets the
+    /// `Sec-WebSocket-Accept` header computed from the `Sec-WebSocket-Key` sent by the client.
+    pub fn websocket_handshake(client_key: &str) -> Self {
+        Self::switching_protocols("websocket").header(Header::new(
+            "Sec-WebSocket-Accept",
+            &websocket_accept(client_key),
+        ))
+    }
+
+    /// Create a http response for a stream of server-sent events. Sets the
+    /// `Content-Type: text/event-stream` and `Cache-Control: no-cache` headers. The events should
+    /// be written after the head of the response, e.g. formatted with [crate::sse_event]. The
+    /// `Connection: close` header is also set, since the stream ends when the connection closes.
+    pub fn event_stream() -> Self {
+        Self::empty()
+            .header(Header::new("Content-Type", content_type::EVENT_STREAM))
+            .header(Header::new("Cache-Control", "no-cache"))
+            .header(Header::new("Connection", "close"))
+    }
+
     /// Create a `html` http response. This method is the same as [Response::content], but it
     /// automatically sets the `Content-Type: text/html` header.
     pub fn html(content: &str) -> Self {
-        Self::content(content, "text/html")
+        Self::content(content, content_type::HTML)
     }
 
     /// Create a `json` http response. This method is the same as [Response::content], but it
     /// automatically sets the `Content-Type: application/json` header.
     pub fn json(content: &str) -> Self {
-        Self::content(content, "application/json")
+        Self::content(content, content_type::JSON)
+    }
+
+    /// Create a response to a `TRACE` request, which echoes the request back as the body with the
+    /// `Content-Type: message/http` header. The `Authorization`, `Proxy-Authorization` and
+    /// `Cookie` headers are left out of the echo, so credentials are not exposed.
+    pub fn trace_echo(request: &Request) -> Self {
+        let mut echo = request.clone();
+        echo.headers.retain(|h| {
+            !h.key.eq_ignore_ascii_case("Authorization")
+                && !h.key.eq_ignore_ascii_case("Proxy-Authorization")
+                && !h.key.eq_ignore_ascii_case("Cookie")
+        });
+
+        Self::content(&echo.to_string(), "message/http")
+    }
+
+    /// Create an error http response with the given status and a plain text body containing the
+    /// message. The `Content-Type: text/plain` and `Content-Length` headers are set.
+    pub fn error(status: Status, message: &str) -> Self {
+        Self::content(message, content_type::PLAIN).status(status)
+    }
+
+    /// Create a simple `html` page for the given status, e.g. `<h1>404 NOT FOUND</h1>`, for use
+    /// in a catch-all error handler. The `Content-Type` and `Content-Length` headers are set.
+    pub fn default_page(status: Status) -> Self {
+        let html = format!("<h1>{}</h1>", escape_html(&status.to_string()));
+
+        Self::html(&html).status(status)
+    }
+
+    /// Create a `html` http response by substituting values into a template. Each `{{key}}`
+    /// placeholder is replaced with the html escaped value for that key, and placeholders with
+    /// no matching key are left as they are.
+    pub fn render(template: &str, vars: &[(&str, &str)]) -> Self {
+        Self::html(&render_template(template, vars))
+    }
+
+    /// Create a http response with the contents of a file as the body. Sets the `Content-Type`
+    /// header based on the file extension, and the `Content-Length` header to the length of the
+    /// file.
+    pub fn from_file(path: &str) -> Result<Self, Error> {
+        let content = fs::read(path)?;
+        let content_length = content.len() as u64;
+
+        Ok(Response {
+            content,
+            ..Self::empty()
+        }
+        .header(Header::new("Content-Type", guess_mime(path)))
+        .header(Header::new("Content-Length", &content_length.to_string())))
+    }
+
+    /// Create a http response serving a file. This method is the same as [Response::from_file],
+    /// but it also sets the `Last-Modified` header from the file metadata.
+    pub fn serve_file(path: &str) -> Result<Self, Error> {
+        let modified = fs::metadata(path)?.modified()?;
+
+        Ok(
+            Self::from_file(path)?
+                .header(Header::new("Last-Modified", &format_http_date(modified))),
+        )
+    }
+
+    /// Create a http response serving a file. This method is the same as [Response::serve_file],
+    /// but it returns a `404 Not Found` response when the file does not exist, and a
+    /// `500 Internal Server Error` response when it exists but cannot be read.
+    pub fn serve_file_or_404(path: &str) -> Self {
+        match Self::serve_file(path) {
+            Ok(response) => response,
+            Err(_) => match fs::metadata(path) {
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                    Self::empty().status(Status::NotFound)
+                }
+                _ => Self::empty().status(Status::InternalServerError),
+            },
+        }
+    }
+
+    /// Create a redirect http response with the given status. Sets the `Location` header to the
+    /// provided location, percent-encoding any characters which are not valid in a url. It is up
+    /// to the consumer to provide a redirect status such as `303`.
+    pub fn redirect(location: &str, status: Status) -> Self {
+        Self::empty()
+            .status(status)
+            .header(Header::new("Location", &percent_encode(location)))
+    }
+
+    /// Create a redirect http response to an absolute url, for clients which require one. The
+    /// `Location` header is set to `base` joined with `path` by a single slash, e.g.
+    /// `https://example.com` and `/login` become `https://example.com/login`. This method is the
+    /// same as [Response::redirect] otherwise.
+    pub fn redirect_absolute(base: &str, path: &str, status: Status) -> Self {
+        let location = format!(
+            "{}/{}",
+            base.trim_end_matches('/'),
+            path.trim_start_matches('/')
+        );
+
+        Self::redirect(&location, status)
+    }
+
+    /// Create a redirect http response with a short `html` body linking to the location, for
+    /// clients which do not follow redirects automatically. This method is the same as
+    /// [Response::redirect], but it also sets the `Content-Type` and `Content-Length` headers.
+    pub fn redirect_with_body(location: &str, status: Status) -> Self {
+        let location = percent_encode(location);
+        let escaped = escape_html(&location);
+        let html = format!("<a href=\"{}\">{}</a>", escaped, escaped);
+
+        Self::html(&html)
+            .status(status)
+            .header(Header::new("Location", &location))
+    }
+
+    /// Create a `405 Not Allowed` http response. Sets the `Allow` header to the methods which are
+    /// permitted for the requested resource.
+    pub fn method_not_allowed(methods: &[Method]) -> Self {
+        Self::empty().status(Status::NotAllowed).allow(methods)
+    }
+
+    /// Create a `204 No Content` http response to a CORS preflight request. Sets the
+    /// `Access-Control-Allow-*` headers to the given origin, methods and headers, and allows the
+    /// preflight response to be cached for a day.
+    pub fn preflight(allowed_methods: &[Method], allowed_headers: &[&str], origin: &str) -> Self {
+        let methods: Vec<String> = allowed_methods.iter().map(|m| m.to_string()).collect();
+
+        Self::empty()
+            .status(Status::NoContent)
+            .header(Header::new("Access-Control-Allow-Origin", origin))
+            .header(Header::new(
+                "Access-Control-Allow-Methods",
+                &methods.join(", "),
+            ))
+            .header(Header::new(
+                "Access-Control-Allow-Headers",
+                &allowed_headers.join(", "),
+            ))
+            .header(Header::new("Access-Control-Max-Age", "86400"))
+    }
+
+    /// Set the `Allow` header of the http response to the given methods.
+    pub fn allow(self, methods: &[Method]) -> Self {
+        let allowed: Vec<String> = methods.iter().map(|m| m.to_string()).collect();
+
+        self.header(Header::new("Allow", &allowed.join(", ")))
     }
 
     /// Add a cookie to the http response.
@@ -62,9 +326,41 @@ impl Response {
         self.header(Header::new("Set-Cookie", content))
     }
 
-    /// Set the status of the http response.
+    /// Set the status of the http response. This also clears any custom reason phrase, so the
+    /// default phrase of the new status is used.
     pub fn status(self, status: Status) -> Self {
-        Response { status, ..self }
+        Response {
+            status,
+            reason: None,
+            ..self
+        }
+    }
+
+    /// Set a custom reason phrase for the status of the http response, keeping the numeric
+    /// status code. Without a custom phrase, the default phrase of the status is used. Returns an
+    /// error if the phrase contains line breaks or other control characters.
+    pub fn reason(self, phrase: &str) -> Result<Self, Error> {
+        validate_reason(phrase)?;
+
+        Ok(Response {
+            reason: Some(phrase.to_string()),
+            ..self
+        })
+    }
+
+    /// Set the scheme of the http response, e.g. `Scheme::Https` or `"HTTPS"`. The `http` and
+    /// `https` schemes are always written in uppercase, and other schemes as they are given.
+    pub fn set_scheme(self, scheme: impl Into<Scheme>) -> Self {
+        Response {
+            scheme: scheme.into(),
+            ..self
+        }
+    }
+
+    /// Create a copy of the http response with a different status, leaving the original as it
+    /// is. This is useful for deriving several responses from a shared template.
+    pub fn with_status(&self, status: Status) -> Response {
+        self.clone().status(status)
     }
 
     /// Add a header to the http response.
@@ -75,189 +371,2417 @@ impl Response {
         Response { headers, ..self }
     }
 
-    fn parse_protocol(line: &str) -> Result<(&str, &str), Error> {
-        let parser_err = Error {
-            err_type: ErrorType::ParserError,
-            msg: "Invalid protocol format".to_string(),
-        };
+    /// Keep the connection open after the http response. Sets the `Connection: keep-alive` header,
+    /// and the `Keep-Alive` header with the idle timeout in seconds and the maximum number of
+    /// requests allowed on the connection.
+    pub fn keep_alive_params(self, timeout: u32, max: u32) -> Self {
+        self.header(Header::new("Connection", "keep-alive"))
+            .header(Header::new(
+                "Keep-Alive",
+                &format!("timeout={}, max={}", timeout, max),
+            ))
+    }
 
-        let mut parts = line.split("/");
+    /// Set a standard set of security headers on the http response. Enables HSTS for a year
+    /// including subdomains, disables content type sniffing, denies framing, and sends no
+    /// referrer.
+    pub fn security_headers(self) -> Self {
+        self.hsts(31536000, true)
+            .nosniff()
+            .frame_options("DENY")
+            .referrer_policy("no-referrer")
+    }
 
-        let scheme = match parts.next() {
-            Some(scheme) => scheme,
-            None => return Err(parser_err),
-        };
+    /// Set the `Strict-Transport-Security` header of the http response, with the given max age in
+    /// seconds.
+    pub fn hsts(self, max_age: u64, include_subdomains: bool) -> Self {
+        let mut value = format!("max-age={}", max_age);
+        if include_subdomains {
+            value.push_str("; includeSubDomains");
+        }
 
-        let version = match parts.next() {
-            Some(version) => version,
-            None => return Err(parser_err),
-        };
+        self.header(Header::new("Strict-Transport-Security", &value))
+    }
 
-        Ok((scheme, version))
-    }    
+    /// Set the `X-Content-Type-Options: nosniff` header of the http response.
+    pub fn nosniff(self) -> Self {
+        self.header(Header::new("X-Content-Type-Options", "nosniff"))
+    }
 
-    fn parse_header(line: &str) -> Result<Header, Error> {
-        let parser_err = Error {
-            err_type: ErrorType::ParserError,
-            msg: "Invalid header format".to_string(),
-        };
+    /// Set the `X-Frame-Options` header of the http response, e.g. to `DENY` or `SAMEORIGIN`.
+    pub fn frame_options(self, value: &str) -> Self {
+        self.header(Header::new("X-Frame-Options", value))
+    }
 
-        let mut parts = line.split(": ");
+    /// Set the `Referrer-Policy` header of the http response.
+    pub fn referrer_policy(self, policy: &str) -> Self {
+        self.header(Header::new("Referrer-Policy", policy))
+    }
 
-        let key = match parts.next() {
-            Some(key) => key,
-            None => return Err(parser_err),
-        };
+    /// Set the `Content-Security-Policy` header of the http response.
+    pub fn csp(self, csp: Csp) -> Self {
+        self.header(Header::new("Content-Security-Policy", &csp.to_string()))
+    }
 
-        let value = match parts.next() {
-            Some(value) => value,
-            None => return Err(parser_err),
-        };
+    /// Set the `Age` header of the http response to the number of seconds it has been cached
+    /// for, replacing any existing `Age` header.
+    pub fn age(self, seconds: u64) -> Self {
+        self.replace_header("Age", &seconds.to_string())
+    }
 
-        Ok(Header::new(key, value))
+    /// Add several headers to the http response at once, in the order given.
+    pub fn with_headers<I: IntoIterator<Item = Header>>(self, headers: I) -> Self {
+        let mut response = self;
+        response.headers.extend(headers);
+
+        response
     }
 
-    pub fn parse(buffer: &str) -> Result<Response, Error> {
-        let parser_err = Error {
-            err_type: ErrorType::ParserError,
-            msg: "Invalid response format".to_string(),
-        };
-        let mut body_parts = buffer.split("\r\n\r\n");
-        let hpart = match body_parts.next() {
-            Some(hpart) => hpart,
-            None => return Err(parser_err),
-        };
-        let body = body_parts.next().unwrap_or("").to_string();
+    /// Add a language to the `Content-Language` header of the http response. Calling this more
+    /// than once lists each language in the same header, e.g. `Content-Language: en, fr`.
+    pub fn content_language(self, lang: &str) -> Self {
+        self.append_header("Content-Language", lang)
+    }
 
-        let mut parts = hpart.split("\r\n");
+    /// Set the `Content-Type` header of the http response from a file extension, e.g. `json` or
+    /// `.json`. Unknown extensions use `application/octet-stream`.
+    pub fn content_type_for_extension(self, ext: &str) -> Self {
+        let mime = guess_mime(&format!(".{}", ext.trim_start_matches('.')));
 
-        let start_line = match parts.next() {
-            Some(start_line) => start_line,
-            None => return Err(parser_err),
-        };
+        self.replace_header("Content-Type", mime)
+    }
 
-        let mut line_parts = start_line.split(" ");
+    /// Add a link to the `Link` header of the http response, e.g. for pagination or preloading.
+    /// Calling this more than once lists each link in the same header, e.g.
+    /// `Link: </page/2>; rel="next", </page/9>; rel="last"`. Returns an error if the uri contains
+    /// angle brackets or the relation contains quotes, or if either contains control characters.
+    pub fn link(self, uri: &str, rel: &str) -> Result<Self, Error> {
+        if uri.contains(['<', '>'])
+            || rel.contains('"')
+            || uri.chars().chain(rel.chars()).any(|c| c.is_control())
+        {
+            return Err(Error {
+                err_type: ErrorType::InvalidHeader,
+                msg: "Invalid link uri or relation".to_string(),
+            });
+        }
 
-        let protocol = match line_parts.next() {
-            Some(protocol) => protocol,
-            None => return Err(parser_err),
-        };
+        Ok(self.append_header("Link", &format!("<{}>; rel=\"{}\"", uri, rel)))
+    }
 
-        let (scheme, version) = Self::parse_protocol(protocol)?;
-        let status_code = match line_parts.next() {
-            Some(code) => code,
-            None => return Err(parser_err),
-        };
-        let status = match Status::from_str(status_code) {
-            Ok(status) => status,
-            _ => return Err(parser_err),
-        };
+    /// Merge headers with the same name into a single header with a comma separated value, in the
+    /// position of the first occurrence. `Set-Cookie` headers are left as they are, since they
+    /// cannot be combined.
+    pub fn normalize_headers(self) -> Self {
+        let mut response = self;
+        let headers = std::mem::take(&mut response.headers);
 
-        let headers: Vec<Header> = parts.into_iter().flat_map(|h| Self::parse_header(h)).collect();
-        Ok(Response {
-            scheme: scheme.to_string(),
-            version: version.to_string(),
-            status: Status::Ok,
-            headers,
-            content: body,
+        headers.into_iter().fold(response, |res, h| {
+            if h.key.eq_ignore_ascii_case("Set-Cookie") {
+                res.header(h)
+            } else {
+                res.append_header(&h.key, &h.value)
+            }
         })
-    }    
-}
+    }
 
-impl ToString for Response {
-    /// Convert the `Response` to a valid http plaintext response.
-    fn to_string(&self) -> String {
-        let headers = self
-            .headers
+    /// Transform the body of the http response with the given function. If the `Content-Length`
+    /// header is set, it is updated to the length of the new body. A body which is not valid
+    /// UTF-8 is converted lossily before being passed to the function.
+    pub fn map_body<F: FnOnce(String) -> String>(self, f: F) -> Self {
+        let content = f(String::from_utf8_lossy(&self.content).into_owned()).into_bytes();
+        let content_length = content.len() as u64;
+        let response = Response { content, ..self };
+
+        match response.find_header("Content-Length") {
+            Some(_) => response.replace_header("Content-Length", &content_length.to_string()),
+            None => response,
+        }
+    }
+
+    /// Turn the http response into an error response with the given status and plain text body.
+    /// The `Content-Type` and `Content-Length` headers are updated to match the new body, and all
+    /// other headers are kept.
+    pub fn into_error(self, status: Status, body: &str) -> Self {
+        let content_length = body.len() as u64;
+
+        Response {
+            content: body.as_bytes().to_vec(),
+            ..self.status(status)
+        }
+        .replace_header("Content-Type", content_type::PLAIN)
+        .replace_header("Content-Length", &content_length.to_string())
+    }
+
+    /// Set the `Content-Length` header of the http response to an explicit value, e.g. for a
+    /// response to a `HEAD` request. This overrides the length computed from the body, and the
+    /// value is sent as it is even when it does not match the body.
+    pub fn with_content_length_override(self, len: u64) -> Self {
+        self.replace_header("Content-Length", &len.to_string())
+    }
+
+    /// Create a gzip compressed copy of the http response, leaving the original untouched, e.g. to
+    /// cache both variants. The copy has the `Content-Encoding: gzip` header, and its
+    /// `Content-Length` header is updated if it is set. An `ETag` header gets a `-gzip` suffix,
+    /// since the compressed body is a different representation, and `Accept-Encoding` is added to
+    /// the `Vary` header. If compressing does not make the body smaller, e.g. for an empty or
+    /// already compressed body, an unchanged copy of the response is returned instead. Returns an
+    /// error if the body already has a content or transfer encoding.
+    pub fn with_compressed_variant(&self) -> Result<Response, Error> {
+        if self.find_header("Content-Encoding").is_some() || self.transfer_encoding().is_some() {
+            return Err(Error {
+                err_type: ErrorType::InvalidHeader,
+                msg: "Response body is already encoded".to_string(),
+            });
+        }
+
+        let content = gzip(&self.content);
+        if content.len() >= self.content.len() {
+            return Ok(self.clone());
+        }
+
+        let content_length = content.len() as u64;
+        let mut response = Response {
+            content,
+            ..self.clone()
+        }
+        .header(Header::new("Content-Encoding", "gzip"));
+
+        if response.find_header("Content-Length").is_some() {
+            response = response.replace_header("Content-Length", &content_length.to_string());
+        }
+
+        if let Some(etag) = response.etag() {
+            let etag = match etag.strip_suffix('"') {
+                Some(tag) => format!("{}-gzip\"", tag),
+                None => format!("{}-gzip", etag),
+            };
+            response = response.replace_header("ETag", &etag);
+        }
+
+        let varies = Self::header_values(&response.headers, "Vary")
             .iter()
-            .fold(String::new(), |a, b| a + &b.to_string() + "\r\n");
+            .flat_map(|value| value.split(','))
+            .any(|name| name.trim() == "*" || name.trim().eq_ignore_ascii_case("Accept-Encoding"));
+        if !varies {
+            response = response.append_header("Vary", "Accept-Encoding");
+        }
 
-        format!(
-            "{}/{} {}\r\n{}\r\n{}",
-            self.scheme,
-            self.version,
-            self.status.to_string(),
-            headers,
-            self.content
-        )
+        Ok(response)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use crate::Header;
-    use crate::Response;
-    use crate::Status;
+    /// Set the `Digest` header of the http response to the SHA-256 hash of the body, e.g.
+    /// `Digest: sha-256=X48E9qOokqqrvdts8nOJRJN3OWDUoyWxBf7kbu9DBPE=`. The hash covers the body
+    /// as it is sent, so this should be called after the body is compressed or otherwise encoded.
+    /// Requires the `digest` feature.
+    #[cfg(feature = "digest")]
+    pub fn content_digest(self) -> Self {
+        use crate::base64;
+        use sha2::{Digest, Sha256};
 
-    #[test]
-    fn empty_response_scheme() {
-        let result = Response::empty();
+        let digest = format!("sha-256={}", base64::encode(&Sha256::digest(&self.content)));
 
-        assert!(result.to_string().contains("HTTP"));
+        self.replace_header("Digest", &digest)
     }
 
-    #[test]
-    fn empty_response_version() {
-        let result = Response::empty();
+    /// Remove the hop-by-hop headers from the http response, so it can be forwarded by a proxy.
+    /// This removes `Connection`, `Keep-Alive`, `Transfer-Encoding`, `TE`, `Trailer`, `Upgrade`
+    /// and `Proxy-*` headers, as well as any headers named in the `Connection` header. A chunked
+    /// body is decoded and sent with a `Content-Length` header instead, and an error is returned
+    /// if it is malformed.
+    pub fn strip_hop_by_hop(self) -> Result<Self, Error> {
+        let mut hop_by_hop: Vec<String> = vec![
+            "connection".to_string(),
+            "keep-alive".to_string(),
+            "transfer-encoding".to_string(),
+            "te".to_string(),
+            "trailer".to_string(),
+            "upgrade".to_string(),
+        ];
 
-        assert!(result.to_string().contains("1.1"));
+        hop_by_hop.extend(self.connection_tokens());
+
+        let mut response = self.decode_chunked_body()?;
+        response.headers.retain(|h| {
+            let key = h.key.to_lowercase();
+            !hop_by_hop.contains(&key) && !key.starts_with("proxy-")
+        });
+
+        Ok(response)
     }
 
-    #[test]
-    fn empty_response_status() {
-        let result = Response::empty();
+    /// Merge another http response into this one. The status, reason phrase and body of the
+    /// overlay are used, and the headers of both responses are combined, with the overlay's
+    /// headers replacing any base headers of the same name.
+    pub fn merge(self, overlay: Response) -> Response {
+        let mut headers = self.headers;
+        headers.retain(|h| {
+            !overlay
+                .headers
+                .iter()
+                .any(|o| o.key.eq_ignore_ascii_case(&h.key))
+        });
+        headers.extend(overlay.headers);
 
-        assert!(result.to_string().contains("200 OK"));
+        Response {
+            status: overlay.status,
+            reason: overlay.reason,
+            content: overlay.content,
+            headers,
+            ..self
+        }
     }
 
-    #[test]
-    fn response_content() {
-        let html = "<html><head><title>Hello, world!</title></head><body><h1>Hello, world!</h1></body></html>";
-        let result = Response::content(html, "text/html");
+    /// Turn the http response into a `304 Not Modified` response for a revalidated cache entry.
+    /// The body is removed along with the `Content-Length`, `Content-Type` and
+    /// `Transfer-Encoding` headers, and validators such as `ETag`, `Last-Modified` and
+    /// `Cache-Control` are kept.
+    pub fn to_not_modified(self) -> Self {
+        let mut response = self.status(Status::NotModified);
+        response.content = Vec::new();
+        response.headers.retain(|h| {
+            !h.key.eq_ignore_ascii_case("Content-Length")
+                && !h.key.eq_ignore_ascii_case("Content-Type")
+                && !h.key.eq_ignore_ascii_case("Transfer-Encoding")
+        });
 
-        assert!(result.to_string().contains(html));
+        response
     }
 
-    #[test]
-    fn response_content_length_header() {
-        let html = "<html><head><title>Hello, world!</title></head><body><h1>Hello, world!</h1></body></html>";
-        let result = Response::content(html, "text/html");
+    /// Convert the http response to HTTP/1.0 for legacy clients. A chunked body is decoded and
+    /// sent with a `Content-Length` header instead, since HTTP/1.0 has no chunked encoding. The
+    /// `Connection: close` header is set unless the response explicitly keeps the connection
+    /// alive. Returns an error if the chunked body is malformed.
+    pub fn downgrade_to_http10(self) -> Result<Self, Error> {
+        let response = Response {
+            version: "1.0".to_string(),
+            ..self
+        }
+        .decode_chunked_body()?;
 
-        assert!(result.to_string().contains("Content-Length: 89"));
+        if response.should_keep_alive() {
+            Ok(response)
+        } else {
+            Ok(response.replace_header("Connection", "close"))
+        }
     }
 
-    #[test]
-    fn set_status() {
-        let result = Response::empty().status(Status::Forbidden);
+    /// Add a header to the http response, returning an error if the header is not valid. Use this
+    /// instead of [Response::header] when the header is built from untrusted input. Adding a
+    /// `Content-Length` header to a chunked response, or chunked `Transfer-Encoding` to a response
+    /// with a `Content-Length` header, is also an error, since the framing would be ambiguous. A
+    /// `Content-Length` header replaces any existing one, so the response never has two lengths.
+    pub fn try_header(self, header: Header) -> Result<Self, Error> {
+        header.validate()?;
 
-        assert!(result.to_string().contains("403 FORBIDDEN"));
+        let is_chunked = header.key.eq_ignore_ascii_case("Transfer-Encoding")
+            && header
+                .value
+                .split(',')
+                .any(|x| x.trim().eq_ignore_ascii_case("chunked"));
+        let conflicts = (header.key.eq_ignore_ascii_case("Content-Length") && self.is_chunked())
+            || (is_chunked && self.find_header("Content-Length").is_some());
+
+        if conflicts {
+            return Err(Error {
+                err_type: ErrorType::InvalidHeader,
+                msg: format!("{} header conflicts with the response framing", header.key),
+            });
+        }
+
+        if header.key.eq_ignore_ascii_case("Content-Length") {
+            return Ok(self.replace_header(&header.key, &header.value));
+        }
+
+        Ok(self.header(header))
     }
 
-    #[test]
-    fn set_header() {
-        let result = Response::empty().header(Header::new("Access-Control-Allow-Origin", "*"));
+    /// Get the scheme of the http response.
+    pub fn scheme(&self) -> &str {
+        self.scheme.as_str()
+    }
 
-        assert!(result
-            .to_string()
-            .contains("Access-Control-Allow-Origin: *"));
+    /// Get the body of the http response.
+    pub fn get_content(&self) -> &[u8] {
+        &self.content
     }
 
-    #[test]
-    fn set_header_does_not_override_existing_headers() {
-        let html = "<html><head><title>Hello, world!</title></head><body><h1>Hello, world!</h1></body></html>";
-        let result = Response::content(html, "text/html")
-            .header(Header::new("Access-Control-Allow-Origin", "*"));
+    /// Check whether the http response has an empty body.
+    pub fn is_empty(&self) -> bool {
+        self.content.is_empty()
+    }
 
-        assert!(result.to_string().contains("Content-Length: 89"));
+    /// Get the length of the body of the http response in bytes.
+    pub fn content_len(&self) -> usize {
+        self.content.len()
     }
 
-    #[test]
-    fn response_format() {
-        let html = "<html><head><title>Hello, world!</title></head><body><h1>Hello, world!</h1></body></html>";
-        let result = Response::content(html, "text/html")
-            .status(Status::SeeOther)
-            .to_string();
-        let expected = "HTTP/1.1 303 SEE OTHER\r\nContent-Type: text/html\r\nContent-Length: 89\r\n\r\n<html><head><title>Hello, world!</title></head><body><h1>Hello, world!</h1></body></html>";
+    /// Get a preview of the body of the http response for logging, with at most `max` bytes of
+    /// the body followed by `...` when it is truncated. The body is never cut in the middle of a
+    /// multibyte character, and a body which is not valid UTF-8 is converted lossily.
+    pub fn body_preview(&self, max: usize) -> String {
+        let body = String::from_utf8_lossy(&self.content);
+        if body.len() <= max {
+            return body.into_owned();
+        }
+
+        let mut end = max;
+        while !body.is_char_boundary(end) {
+            end -= 1;
+        }
+
+        format!("{}...", &body[..end])
+    }
+
+    /// Get the number of characters in the body of the http response, which differs from
+    /// [Response::content_len] for multibyte UTF-8 content. A body which is not valid UTF-8 is
+    /// converted lossily before counting.
+    pub fn char_count(&self) -> usize {
+        String::from_utf8_lossy(&self.content).chars().count()
+    }
+
+    /// Convert the headers of the http response into a map from the lowercased header name to all
+    /// of the values for that header, in the order they were set.
+    pub fn into_header_map(self) -> HashMap<String, Vec<String>> {
+        let mut map: HashMap<String, Vec<String>> = HashMap::new();
+
+        for header in self.headers {
+            map.entry(header.key.to_lowercase())
+                .or_default()
+                .push(header.value);
+        }
+
+        map
+    }
+
+    /// Get the number of `Set-Cookie` headers of the http response.
+    pub fn cookie_count(&self) -> usize {
+        Self::header_values(&self.headers, "Set-Cookie").len()
+    }
+
+    /// Check that the http response sets at most `max` cookies, since some browsers ignore
+    /// responses with too many `Set-Cookie` headers. Returns an error if there are more.
+    pub fn check_cookie_limit(&self, max: usize) -> Result<(), Error> {
+        let count = self.cookie_count();
+
+        if count > max {
+            return Err(Error {
+                err_type: ErrorType::InvalidHeader,
+                msg: format!(
+                    "Response sets {} cookies, more than the limit of {}",
+                    count, max
+                ),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Get the value of the `Content-Length` header of the http response. Returns `None` if the
+    /// header is missing or is not a valid length. The length is a `u64` rather than a `usize`,
+    /// so large declared lengths are read correctly on 32-bit targets.
+    pub fn content_length(&self) -> Option<u64> {
+        match self.find_header("Content-Length") {
+            Some(length) => length.trim().parse().ok(),
+            None => None,
+        }
+    }
+
+    /// Get the value of the `Content-Type` header of the http response.
+    pub fn content_type(&self) -> Option<&str> {
+        self.find_header("Content-Type")
+    }
+
+    /// Get the value of the `Location` header of the http response.
+    pub fn location(&self) -> Option<&str> {
+        self.find_header("Location")
+    }
+
+    /// Get the value of the `ETag` header of the http response.
+    pub fn etag(&self) -> Option<&str> {
+        self.find_header("ETag")
+    }
+
+    /// Check whether the `ETag` of the http response matches the `If-None-Match` header sent by a
+    /// client, meaning a `304 Not Modified` response can be sent instead. The header may be `*` or
+    /// a comma separated list of tags, and tags are compared weakly, so `W/"v1"` matches `"v1"`.
+    pub fn matches_etag(&self, if_none_match: &str) -> bool {
+        if if_none_match.trim() == "*" {
+            return true;
+        }
+
+        let etag = match self.etag() {
+            Some(etag) => Self::weak_etag(etag),
+            None => return false,
+        };
+
+        if_none_match
+            .split(',')
+            .any(|tag| Self::weak_etag(tag) == etag)
+    }
+
+    // Get the opaque part of an entity tag for weak comparison, without the `W/` prefix.
+    fn weak_etag(tag: &str) -> &str {
+        let tag = tag.trim();
+
+        tag.strip_prefix("W/").unwrap_or(tag)
+    }
+
+    /// Get the value of the `Transfer-Encoding` header of the http response.
+    pub fn transfer_encoding(&self) -> Option<&str> {
+        self.find_header("Transfer-Encoding")
+    }
+
+    /// Check whether the body of the http response uses chunked transfer encoding.
+    pub fn is_chunked(&self) -> bool {
+        match self.transfer_encoding() {
+            Some(encoding) => encoding
+                .split(',')
+                .any(|x| x.trim().eq_ignore_ascii_case("chunked")),
+            None => false,
+        }
+    }
+
+    /// Check whether the client can find the end of the http response without the connection
+    /// being closed, so it is safe to send on a pipelined connection. This is the case when the
+    /// response has a valid `Content-Length` header, uses chunked transfer encoding, or has a
+    /// status which forbids a body. A response without either header is also delimited when a
+    /// `Content-Length` header is added for it as it is serialized.
+    pub fn is_self_delimiting(&self) -> bool {
+        self.status.forbids_body()
+            || self.is_chunked()
+            || self.content_length().is_some()
+            || self.adds_content_length()
+    }
+
+    /// Check whether the connection should be kept open after the http response. HTTP/1.1
+    /// connections are kept open unless the `Connection` header contains `close`, while HTTP/1.0
+    /// connections are closed unless the `Connection` header contains `keep-alive`.
+    pub fn should_keep_alive(&self) -> bool {
+        let tokens = self.connection_tokens();
+        let has_token = |token: &str| tokens.iter().any(|x| x == token);
+
+        if self.version == "1.0" {
+            has_token("keep-alive")
+        } else {
+            !has_token("close")
+        }
+    }
+
+    /// Get the tokens listed in the `Connection` headers of the http response, in lowercase, e.g.
+    /// `Connection: keep-alive, Upgrade` gives `keep-alive` and `upgrade`.
+    pub fn connection_tokens(&self) -> Vec<String> {
+        Self::header_values(&self.headers, "Connection")
+            .iter()
+            .flat_map(|value| value.split(','))
+            .map(|token| token.trim().to_lowercase())
+            .filter(|token| !token.is_empty())
+            .collect()
+    }
+
+    /// Get the reason phrase of the http response. For a parsed response this is the phrase
+    /// exactly as it appeared in the status line, even when it differs from the canonical phrase,
+    /// and for a phrase set with [Response::reason] it is that phrase. Otherwise, including when a
+    /// parsed status line has no phrase, it is the default phrase of the status.
+    pub fn reason_phrase(&self) -> &str {
+        match &self.reason {
+            Some(reason) => reason,
+            None => self.status.message(),
+        }
+    }
+
+    /// Build a cache key from the request headers named in the `Vary` header of the http
+    /// response. The key lists each named header with its values, sorted by name, so it does not
+    /// depend on the order of the headers. Returns `None` for a response with `Vary: *`, which
+    /// cannot be cached.
+    pub fn vary_key(&self, request_headers: &[Header]) -> Option<String> {
+        let mut names: Vec<String> = Self::header_values(&self.headers, "Vary")
+            .iter()
+            .flat_map(|value| value.split(','))
+            .map(|name| name.trim().to_lowercase())
+            .filter(|name| !name.is_empty())
+            .collect();
+
+        if names.iter().any(|name| name == "*") {
+            return None;
+        }
+
+        names.sort();
+        names.dedup();
+
+        Some(
+            names
+                .iter()
+                .map(|name| {
+                    let values = Self::header_values(request_headers, name);
+                    format!("{}: {}\n", name, values.join(", "))
+                })
+                .collect(),
+        )
+    }
+
+    /// Describe the differences between this http response and another one, in terms of the
+    /// protocol, status, headers and body. Returns an empty list when the responses are the same.
+    pub fn diff(&self, other: &Response) -> Vec<String> {
+        let mut diffs = Vec::new();
+
+        let protocol = format!("{}/{}", self.scheme, self.version);
+        let other_protocol = format!("{}/{}", other.scheme, other.version);
+        if protocol != other_protocol {
+            diffs.push(format!("protocol: {} != {}", protocol, other_protocol));
+        }
+
+        if self.status != other.status {
+            diffs.push(format!("status: {} != {}", self.status, other.status));
+        }
+
+        let mut keys: Vec<String> = Vec::new();
+        for header in self.headers.iter().chain(other.headers.iter()) {
+            let key = header.key.to_lowercase();
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+
+        for key in keys {
+            let values = Self::header_values(&self.headers, &key);
+            let other_values = Self::header_values(&other.headers, &key);
+            if values != other_values {
+                diffs.push(format!(
+                    "header {}: {:?} != {:?}",
+                    key, values, other_values
+                ));
+            }
+        }
+
+        if self.content != other.content {
+            diffs.push(format!(
+                "body: {:?} != {:?}",
+                String::from_utf8_lossy(&self.content),
+                String::from_utf8_lossy(&other.content)
+            ));
+        }
+
+        diffs
+    }
+
+    /// Convert the `Response` to a valid http message. Unlike `to_string`, the body is written as
+    /// is, so this should be used for bodies which are not valid UTF-8. The body is left out for
+    /// statuses which forbid one, i.e. `1xx`, `204` and `304`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.head_string().into_bytes();
+        bytes.extend_from_slice(self.sent_content());
+
+        bytes
+    }
+
+    /// Write the status line and headers of the http response, followed by a body copied from
+    /// the reader, so that a large body never needs to be held in memory. Returns the number of
+    /// body bytes written.
+    pub fn write_body_from<R: Read, W: Write>(
+        &self,
+        reader: &mut R,
+        writer: &mut W,
+    ) -> io::Result<u64> {
+        writer.write_all(self.head_string().as_bytes())?;
+        let written = io::copy(reader, writer)?;
+        writer.flush()?;
+
+        Ok(written)
+    }
+
+    /// Write the http response to the writer and flush it. Io errors are converted into this
+    /// crate's [Error], so server code can use a single error type.
+    pub fn send<W: Write>(&self, w: &mut W) -> Result<(), Error> {
+        w.write_all(&self.to_bytes())?;
+        w.flush()?;
+
+        Ok(())
+    }
+
+    /// Write the http response to the writer in slices of at most `chunk` bytes, flushing after
+    /// each one, to limit how much is written to a slow client at once. A chunk size of zero is an
+    /// error.
+    pub fn write_chunked_to<W: Write>(&self, w: &mut W, chunk: usize) -> io::Result<()> {
+        if chunk == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Chunk size must be greater than zero",
+            ));
+        }
+
+        for slice in self.to_bytes().chunks(chunk) {
+            w.write_all(slice)?;
+            w.flush()?;
+        }
+
+        Ok(())
+    }
+
+    /// Convert the status line and headers of the `Response` to valid http plaintext, including
+    /// the blank line that separates them from the body. The body itself is not included, so it
+    /// can be written separately. A `Content-Length` header is added for a body with no
+    /// `Content-Length` or `Transfer-Encoding` header, unless the body is empty and the connection
+    /// is closed after the response, and any `Content-Length` header is left out
+    /// for a status which forbids a body or when a `Transfer-Encoding` header frames the body.
+    pub fn head_string(&self) -> String {
+        let forbids_body = self.status.forbids_body();
+        let has_encoding = self.find_header("Transfer-Encoding").is_some();
+        let mut headers = self
+            .headers
+            .iter()
+            .filter(|h| {
+                !((forbids_body || has_encoding) && h.key.eq_ignore_ascii_case("Content-Length"))
+            })
+            .fold(String::new(), |a, b| a + &b.to_string() + "\r\n");
+
+        if self.adds_content_length() {
+            let content_length = self.content.len() as u64;
+            headers += &Header::new("Content-Length", &content_length.to_string()).to_string();
+            headers += "\r\n";
+        }
+
+        format!(
+            "{}/{} {} {}\r\n{}\r\n",
+            self.scheme,
+            self.version,
+            self.status.code(),
+            self.reason_phrase(),
+            headers
+        )
+    }
+
+    // Check whether a `Content-Length` header is added when the http response is serialized. A
+    // body without a framing header is ambiguous on a kept-alive connection, so its length is
+    // added unless the status forbids a body. An empty body is left unframed when the connection
+    // is closed afterwards, since the body may be streamed after the head.
+    fn adds_content_length(&self) -> bool {
+        !self.status.forbids_body()
+            && self.find_header("Transfer-Encoding").is_none()
+            && self.find_header("Content-Length").is_none()
+            && (!self.content.is_empty() || self.should_keep_alive())
+    }
+
+    // Get the body of the http response as it is sent, which is empty when the status forbids a
+    // body.
+    fn sent_content(&self) -> &[u8] {
+        if self.status.forbids_body() {
+            &[]
+        } else {
+            &self.content
+        }
+    }
+
+    // Decode a chunked body, replacing the `Transfer-Encoding` header with a `Content-Length`
+    // header. Other responses are returned as they are.
+    fn decode_chunked_body(self) -> Result<Self, Error> {
+        if !self.is_chunked() {
+            return Ok(self);
+        }
+
+        let content = match decode_chunked(&self.content) {
+            Some(content) => content,
+            None => {
+                return Err(Error {
+                    err_type: ErrorType::ParserError,
+                    msg: "Invalid chunked body".to_string(),
+                })
+            }
+        };
+        let content_length = content.len() as u64;
+
+        let mut response = Response { content, ..self };
+        response
+            .headers
+            .retain(|h| !h.key.eq_ignore_ascii_case("Transfer-Encoding"));
+
+        Ok(response.replace_header("Content-Length", &content_length.to_string()))
+    }
+
+    fn append_header(self, key: &str, value: &str) -> Self {
+        let mut headers = self.headers;
+
+        match headers.iter_mut().find(|h| h.key.eq_ignore_ascii_case(key)) {
+            Some(header) => header.value = format!("{}, {}", header.value, value),
+            None => headers.push(Header::new(key, value)),
+        }
+
+        Response { headers, ..self }
+    }
+
+    fn replace_header(self, key: &str, value: &str) -> Self {
+        let mut headers = self.headers;
+        let mut replaced = false;
+
+        headers.retain_mut(|h| {
+            if !h.key.eq_ignore_ascii_case(key) {
+                return true;
+            }
+            if replaced {
+                return false;
+            }
+
+            *h = Header::new(key, value);
+            replaced = true;
+            true
+        });
+
+        if !replaced {
+            headers.push(Header::new(key, value));
+        }
+
+        Response { headers, ..self }
+    }
+
+    fn header_values<'a>(headers: &'a [Header], key: &str) -> Vec<&'a str> {
+        headers
+            .iter()
+            .filter(|h| h.key.eq_ignore_ascii_case(key))
+            .map(|h| h.value.as_str())
+            .collect()
+    }
+
+    fn find_header(&self, key: &str) -> Option<&str> {
+        find_header(&self.headers, key)
+    }
+
+    fn parse_protocol(line: &str) -> Result<(&str, &str), Error> {
+        let parser_err = Error {
+            err_type: ErrorType::ParserError,
+            msg: "Invalid protocol format".to_string(),
+        };
+
+        let mut parts = line.split("/");
+
+        let scheme = match parts.next() {
+            Some(scheme) if !scheme.is_empty() => scheme,
+            _ => return Err(parser_err),
+        };
+
+        let version = match parts.next() {
+            Some(version) if !version.is_empty() => version,
+            _ => return Err(parser_err),
+        };
+
+        Ok((scheme, version))
+    }
+
+    fn parse_header(line: &str) -> Result<Header, Error> {
+        let parser_err = Error {
+            err_type: ErrorType::ParserError,
+            msg: "Invalid header format".to_string(),
+        };
+
+        let mut parts = line.split(": ");
+
+        let key = match parts.next() {
+            Some(key) if !key.is_empty() => key,
+            _ => return Err(parser_err),
+        };
+
+        let value = match parts.next() {
+            Some(value) => value,
+            None => return Err(parser_err),
+        };
+
+        Ok(Header::new(key, value))
+    }
+
+    fn parse_start_line<'a>(
+        line: &'a str,
+        limits: &ParseLimits,
+    ) -> Result<(&'a str, &'a str, Status, String), Error> {
+        let parser_err = Error {
+            err_type: ErrorType::ParserError,
+            msg: "Invalid status line format".to_string(),
+        };
+
+        // Tokens are separated by runs of whitespace, to tolerate upstreams which send extra
+        // spaces in the status line.
+        let mut line_parts = line.split_whitespace();
+
+        let protocol = match line_parts.next() {
+            Some(protocol) => protocol,
+            None => return Err(parser_err),
+        };
+
+        let (scheme, version) = Self::parse_protocol(protocol)?;
+        let status_code = match line_parts.next() {
+            Some(code) => code,
+            None => return Err(parser_err),
+        };
+        // The reason phrase is optional, and a missing one falls back to the default phrase of
+        // the status.
+        let reason = line_parts.collect::<Vec<&str>>().join(" ");
+        if reason.len() > limits.max_reason_len {
+            return Err(parser_err);
+        }
+
+        let status = match Status::from_str(status_code) {
+            Ok(status) => status,
+            _ => match status_code.parse() {
+                Ok(code) => Status::new(code, &reason)?,
+                Err(_) => return Err(parser_err),
+            },
+        };
+
+        Ok((scheme, version, status, reason))
+    }
+
+    /// Parse a http plaintext response into a `Response` object.
+    pub fn parse(buffer: &str) -> Result<Response, Error> {
+        Self::parse_bytes(buffer.as_bytes())
+    }
+
+    /// Parse several http plaintext responses from one buffer, e.g. pipelined responses read from
+    /// a keep-alive connection. Each response must have a `Content-Length` header unless its
+    /// status forbids a body. Returns the complete responses in order, along with any trailing
+    /// partial response which has not been fully received yet.
+    pub fn parse_many(buffer: &str) -> Result<(Vec<Response>, &str), Error> {
+        let mut responses = Vec::new();
+        let mut rest = buffer;
+
+        while !rest.is_empty() {
+            let head_len = match rest.find("\r\n\r\n") {
+                Some(i) => i + 4,
+                None => break,
+            };
+
+            let response = Self::parse(&rest[..head_len])?;
+            let length_err = Error {
+                err_type: ErrorType::ParserError,
+                msg: "Response has no valid Content-Length header".to_string(),
+            };
+
+            let body_len = match response.content_length() {
+                Some(length) => match usize::try_from(length) {
+                    Ok(length) => length,
+                    Err(_) => return Err(length_err),
+                },
+                None if response.status.forbids_body() => 0,
+                None => return Err(length_err),
+            };
+
+            let end = match head_len.checked_add(body_len) {
+                Some(end) => end,
+                None => break,
+            };
+
+            if rest.len() < end {
+                break;
+            }
+
+            // The buffer holds the whole body, so a length which ends inside a character can
+            // never be completed by reading more.
+            let body = match rest.get(head_len..end) {
+                Some(body) => body,
+                None => {
+                    return Err(Error {
+                        err_type: ErrorType::ParserError,
+                        msg: "Content-Length ends inside a character".to_string(),
+                    })
+                }
+            };
+
+            responses.push(Response {
+                content: body.as_bytes().to_vec(),
+                ..response
+            });
+            rest = &rest[end..];
+        }
+
+        Ok((responses, rest))
+    }
+
+    /// Parse only the status line of a http plaintext response, returning the protocol, e.g.
+    /// `HTTP/1.1`, and the status. The headers and body are ignored, so this is cheaper than
+    /// [Response::parse] when only the status is needed.
+    pub fn parse_status_line(buffer: &str) -> Result<(String, Status), Error> {
+        let line = buffer.split("\r\n").next().unwrap_or_default();
+        let (scheme, version, status, _) = Self::parse_start_line(line, &ParseLimits::default())?;
+
+        Ok((format!("{}/{}", scheme, version), status))
+    }
+
+    /// Parse a http plaintext response as far as possible, for diagnosing malformed messages.
+    /// Instead of failing, invalid parts are skipped or replaced with defaults, and a warning
+    /// describing each one is returned alongside the response. Returns no response if the buffer
+    /// is empty.
+    pub fn parse_lenient(buffer: &str) -> (Option<Response>, Vec<String>) {
+        let mut warnings = Vec::new();
+
+        if buffer.trim().is_empty() {
+            warnings.push("Response is empty".to_string());
+            return (None, warnings);
+        }
+
+        let (head, body) = match buffer.split_once("\r\n\r\n") {
+            Some((head, body)) => (head, body),
+            None => {
+                warnings.push("Missing blank line after the headers".to_string());
+                (buffer, "")
+            }
+        };
+
+        let mut lines = head.lines();
+        let mut line_parts = lines.next().unwrap_or_default().split_whitespace();
+
+        let (scheme, version) = match Self::parse_protocol(line_parts.next().unwrap_or_default()) {
+            Ok(protocol) => protocol,
+            Err(_) => {
+                warnings.push("Invalid protocol, defaulting to HTTP/1.1".to_string());
+                ("HTTP", "1.1")
+            }
+        };
+
+        let status_code = line_parts.next().unwrap_or_default();
+        let mut reason = line_parts.collect::<Vec<&str>>().join(" ");
+        if validate_reason(&reason).is_err() {
+            warnings.push(format!(
+                "Invalid reason phrase {:?}, using the default phrase",
+                reason
+            ));
+            reason.clear();
+        }
+
+        let status = match status_code.parse() {
+            Ok(code) => match Status::new(code, &reason) {
+                Ok(status) => status,
+                Err(_) => {
+                    warnings.push(format!("Invalid status code {}, defaulting to 200", code));
+                    Status::Ok
+                }
+            },
+            Err(_) => {
+                warnings.push("Missing or invalid status code, defaulting to 200".to_string());
+                Status::Ok
+            }
+        };
+
+        let mut headers = Vec::new();
+        for line in lines {
+            match Self::parse_header(line) {
+                Ok(header) => headers.push(header),
+                Err(_) => warnings.push(format!("Skipped invalid header line {:?}", line)),
+            }
+        }
+
+        let response = Response {
+            scheme: Scheme::from(scheme),
+            version: version.to_string(),
+            status,
+            headers,
+            content: body.as_bytes().to_vec(),
+            reason: Some(reason).filter(|reason| !reason.is_empty()),
+        };
+
+        (Some(response), warnings)
+    }
+
+    /// Parse a http plaintext response into a `Response` object, rejecting messages which are
+    /// ambiguous about where the body ends. A message with both a `Content-Length` and a
+    /// `Transfer-Encoding` header is an error, since it can be used for request smuggling.
+    pub fn parse_strict(buffer: &str) -> Result<Response, Error> {
+        let response = Self::parse(buffer)?;
+
+        if response.find_header("Content-Length").is_some()
+            && response.transfer_encoding().is_some()
+        {
+            return Err(Error {
+                err_type: ErrorType::ParserError,
+                msg: "Both Content-Length and Transfer-Encoding headers are set".to_string(),
+            });
+        }
+
+        Ok(response)
+    }
+
+    /// Parse a http response into a `Response` object. The status line and headers must be valid
+    /// UTF-8, but the body is kept as raw bytes, so binary bodies can be parsed.
+    pub fn parse_bytes(buffer: &[u8]) -> Result<Response, Error> {
+        Self::parse_with_limits(buffer, &ParseLimits::default())
+    }
+
+    /// Parse a http response into a `Response` object, the same as [Response::parse_bytes], but
+    /// with the given limits instead of the defaults.
+    pub fn parse_with_limits(buffer: &[u8], limits: &ParseLimits) -> Result<Response, Error> {
+        let parser_err = Error {
+            err_type: ErrorType::ParserError,
+            msg: "Invalid response format".to_string(),
+        };
+
+        let (head, body) = match buffer.windows(4).position(|x| x == b"\r\n\r\n") {
+            Some(i) => (&buffer[..i], &buffer[i + 4..]),
+            None => (buffer, &[][..]),
+        };
+
+        let hpart = match std::str::from_utf8(head) {
+            Ok(hpart) => hpart,
+            Err(_) => return Err(parser_err),
+        };
+
+        let mut parts = hpart.split("\r\n");
+
+        let start_line = match parts.next() {
+            Some(start_line) => start_line,
+            None => return Err(parser_err),
+        };
+
+        let (scheme, version, status, reason) = Self::parse_start_line(start_line, limits)?;
+
+        let headers: Vec<Header> = parts.flat_map(Self::parse_header).collect();
+        Ok(Response {
+            scheme: Scheme::from(scheme),
+            version: version.to_string(),
+            status,
+            headers,
+            content: body.to_vec(),
+            reason: Some(reason).filter(|reason| !reason.is_empty()),
+        })
+    }
+}
+
+impl fmt::Display for Response {
+    /// Convert the `Response` to a valid http plaintext response.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}{}",
+            self.head_string(),
+            String::from_utf8_lossy(self.sent_content())
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs;
+    use std::io::Read;
+
+    use crate::content_type;
+    use crate::Csp;
+    use crate::ErrorType;
+    use crate::Header;
+    use crate::Method;
+    use crate::ParseLimits;
+    use crate::Request;
+    use crate::Response;
+    use crate::Scheme;
+    use crate::Status;
+
+    #[test]
+    fn empty_response_scheme() {
+        let result = Response::empty();
+
+        assert!(result.to_string().contains("HTTP"));
+    }
+
+    #[test]
+    fn empty_response_version() {
+        let result = Response::empty();
+
+        assert!(result.to_string().contains("1.1"));
+    }
+
+    #[test]
+    fn empty_response_with_own_content_length() {
+        let result = Response::empty()
+            .header(Header::new("Content-Length", "5"))
+            .head_string();
+        let expected = "HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\n";
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn empty_chunked_response_has_no_content_length() {
+        let result = Response::empty()
+            .header(Header::new("Transfer-Encoding", "chunked"))
+            .to_string();
+        let expected = "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n";
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn empty_response_status() {
+        let result = Response::empty();
+
+        assert!(result.to_string().contains("200 OK"));
+    }
+
+    #[test]
+    fn response_content() {
+        let html = "<html><head><title>Hello, world!</title></head><body><h1>Hello, world!</h1></body></html>";
+        let result = Response::content(html, "text/html");
+
+        assert!(result.to_string().contains(html));
+    }
+
+    #[test]
+    fn response_content_length_header() {
+        let html = "<html><head><title>Hello, world!</title></head><body><h1>Hello, world!</h1></body></html>";
+        let result = Response::content(html, "text/html");
+
+        assert!(result.to_string().contains("Content-Length: 89"));
+    }
+
+    #[test]
+    fn set_status() {
+        let result = Response::empty().status(Status::Forbidden);
+
+        assert!(result.to_string().contains("403 FORBIDDEN"));
+    }
+
+    #[test]
+    fn set_header() {
+        let result = Response::empty().header(Header::new("Access-Control-Allow-Origin", "*"));
+
+        assert!(result
+            .to_string()
+            .contains("Access-Control-Allow-Origin: *"));
+    }
+
+    #[test]
+    fn set_header_does_not_override_existing_headers() {
+        let html = "<html><head><title>Hello, world!</title></head><body><h1>Hello, world!</h1></body></html>";
+        let result = Response::content(html, "text/html")
+            .header(Header::new("Access-Control-Allow-Origin", "*"));
+
+        assert!(result.to_string().contains("Content-Length: 89"));
+    }
+
+    #[test]
+    fn response_format() {
+        let html = "<html><head><title>Hello, world!</title></head><body><h1>Hello, world!</h1></body></html>";
+        let result = Response::content(html, "text/html")
+            .status(Status::SeeOther)
+            .to_string();
+        let expected = "HTTP/1.1 303 SEE OTHER\r\nContent-Type: text/html\r\nContent-Length: 89\r\n\r\n<html><head><title>Hello, world!</title></head><body><h1>Hello, world!</h1></body></html>";
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn head_string_excludes_body() {
+        let html = "<html><head><title>Hello, world!</title></head><body><h1>Hello, world!</h1></body></html>";
+        let response = Response::content(html, "text/html");
+        let result = response.head_string();
+
+        assert!(result.ends_with("\r\n\r\n"));
+        assert!(!result.contains(html));
+        assert_eq!(response.get_content(), html.as_bytes());
+    }
+
+    #[test]
+    fn response_with_protocol() {
+        let result = Response::with_protocol("HTTPS", "2");
+
+        assert!(result.to_string().starts_with("HTTPS/2 200 OK\r\n"));
+    }
+
+    #[test]
+    fn parse_preserves_reason_phrase() {
+        let result = Response::parse("HTTP/1.1 404 NOT FOUND\r\n\r\n").unwrap();
+
+        assert_eq!(result.status, Status::NotFound);
+        assert_eq!(result.reason_phrase(), "NOT FOUND");
+    }
+
+    #[test]
+    fn parse_status_line_without_reason() {
+        let result = Response::parse("HTTP/1.1 404\r\n\r\n").unwrap();
+
+        assert_eq!(result.status, Status::NotFound);
+        assert_eq!(result.reason_phrase(), "NOT FOUND");
+        assert_eq!(
+            result.head_string(),
+            "HTTP/1.1 404 NOT FOUND\r\nContent-Length: 0\r\n\r\n"
+        );
+    }
+
+    #[test]
+    fn empty_response_has_no_content() {
+        let result = Response::empty();
+
+        assert!(result.is_empty());
+        assert_eq!(result.content_len(), 0);
+    }
+
+    #[test]
+    fn response_content_len() {
+        let html = "<html><head><title>Hello, world!</title></head><body><h1>Hello, world!</h1></body></html>";
+        let result = Response::content(html, "text/html");
+
+        assert!(!result.is_empty());
+        assert_eq!(result.content_len(), 89);
+    }
+
+    #[test]
+    fn redirect_encodes_location() {
+        let result = Response::redirect("/hello world", Status::SeeOther);
+
+        assert!(result.to_string().contains("303 SEE OTHER"));
+        assert!(result.to_string().contains("Location: /hello%20world"));
+    }
+
+    #[test]
+    fn redirect_does_not_double_encode_location() {
+        let result = Response::redirect("https://example.com/hello%20world?q=1", Status::SeeOther);
+
+        assert!(result
+            .to_string()
+            .contains("Location: https://example.com/hello%20world?q=1"));
+    }
+
+    #[test]
+    fn method_not_allowed_sets_allow_header() {
+        let result = Response::method_not_allowed(&[Method::GET, Method::POST]).to_string();
+
+        assert!(result.contains("405 NOT ALLOWED"));
+        assert!(result.contains("Allow: GET, POST"));
+    }
+
+    #[test]
+    fn header_map() {
+        let res = "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nSet-Cookie: a=1\r\nset-cookie: b=2\r\n\r\n";
+        let result = Response::parse(res).unwrap().into_header_map();
+
+        assert_eq!(result["content-type"], vec!["text/html"]);
+        assert_eq!(result["set-cookie"], vec!["a=1", "b=2"]);
+    }
+
+    #[test]
+    fn chunked_response() {
+        let result = Response::body("5\r\nhello\r\n0\r\n\r\n")
+            .header(Header::new("Transfer-Encoding", "gzip, chunked"));
+
+        assert!(result.is_chunked());
+        assert_eq!(result.transfer_encoding(), Some("gzip, chunked"));
+    }
+
+    #[test]
+    fn non_chunked_response() {
+        let result = Response::content("hello", "text/plain");
+
+        assert!(!result.is_chunked());
+        assert_eq!(result.transfer_encoding(), None);
+    }
+
+    #[test]
+    fn try_header_rejects_newline() {
+        let result = Response::empty().try_header(Header::new("X-Name", "foo\r\nbar"));
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn try_header_accepts_valid_header() {
+        let result = Response::empty()
+            .try_header(Header::new("X-Name", "foo"))
+            .unwrap();
+
+        assert!(result.to_string().contains("X-Name: foo"));
+    }
+
+    #[test]
+    fn set_content_language() {
+        let result = Response::empty()
+            .content_language("en")
+            .content_language("fr")
+            .to_string();
+
+        assert!(result.contains("Content-Language: en, fr\r\n"));
+    }
+
+    #[test]
+    fn normalize_headers_merges_duplicates() {
+        let result = Response::empty()
+            .header(Header::new("Cache-Control", "no-cache"))
+            .cookie("a=1")
+            .header(Header::new("Cache-Control", "no-store"))
+            .cookie("b=2")
+            .normalize_headers()
+            .to_string();
+        let expected = "HTTP/1.1 200 OK\r\nCache-Control: no-cache, no-store\r\nSet-Cookie: a=1\r\nSet-Cookie: b=2\r\nContent-Length: 0\r\n\r\n";
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn serve_existing_file() {
+        let path = env::temp_dir().join("nanohttp-serve-file.html");
+        fs::write(&path, "<h1>Hello, world!</h1>").unwrap();
+
+        let result = Response::serve_file_or_404(path.to_str().unwrap()).to_string();
+
+        assert!(result.contains("200 OK"));
+        assert!(result.contains("Content-Type: text/html"));
+        assert!(result.contains("Content-Length: 22"));
+        assert!(result.contains("Last-Modified: "));
+        assert!(result.ends_with("<h1>Hello, world!</h1>"));
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn serve_missing_file() {
+        let path = env::temp_dir().join("nanohttp-missing-file.html");
+
+        assert!(Response::serve_file(path.to_str().unwrap()).is_err());
+        assert!(Response::serve_file_or_404(path.to_str().unwrap())
+            .to_string()
+            .contains("404 NOT FOUND"));
+    }
+
+    #[test]
+    fn serve_unreadable_file() {
+        let path = env::temp_dir();
+
+        assert!(Response::serve_file_or_404(path.to_str().unwrap())
+            .to_string()
+            .contains("500 INTERNAL SERVER ERROR"));
+    }
+
+    #[test]
+    fn preflight_response() {
+        let result = Response::preflight(
+            &[Method::GET, Method::POST, Method::OPTIONS],
+            &["Content-Type", "Authorization"],
+            "https://example.com",
+        )
+        .to_string();
+
+        assert!(result.contains("204 NO CONTENT"));
+        assert!(result.contains("Access-Control-Allow-Origin: https://example.com"));
+        assert!(result.contains("Access-Control-Allow-Methods: GET, POST, OPTIONS"));
+        assert!(result.contains("Access-Control-Allow-Headers: Content-Type, Authorization"));
+        assert!(result.contains("Access-Control-Max-Age: 86400"));
+    }
+
+    #[test]
+    fn parse_custom_status() {
+        let res = "HTTP/1.1 599 CUSTOM REASON\r\nContent-Length: 0\r\n\r\n";
+        let result = Response::parse(res).unwrap();
+
+        assert_eq!(result.status.code(), 599);
+        assert_eq!(result.to_string(), res);
+    }
+
+    #[test]
+    fn set_many_headers() {
+        let result = Response::empty()
+            .with_headers(vec![
+                Header::new("Cache-Control", "no-cache"),
+                Header::new("Access-Control-Allow-Origin", "*"),
+                Header::new("X-Request-Id", "42"),
+            ])
+            .to_string();
+        let expected = "HTTP/1.1 200 OK\r\nCache-Control: no-cache\r\nAccess-Control-Allow-Origin: *\r\nX-Request-Id: 42\r\nContent-Length: 0\r\n\r\n";
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn parse_binary_body() {
+        let mut res =
+            b"HTTP/1.1 200 OK\r\nContent-Type: image/png\r\nContent-Length: 4\r\n\r\n".to_vec();
+        res.extend_from_slice(&[0x89, 0x50, 0xff, 0x00]);
+        let result = Response::parse_bytes(&res).unwrap();
+
+        assert_eq!(result.status, Status::Ok);
+        assert_eq!(result.get_content(), &[0x89, 0x50, 0xff, 0x00]);
+        assert_eq!(result.to_bytes(), res);
+    }
+
+    #[test]
+    fn set_keep_alive_params() {
+        let result = Response::empty().keep_alive_params(5, 100).to_string();
+
+        assert!(result.contains("Connection: keep-alive\r\n"));
+        assert!(result.contains("Keep-Alive: timeout=5, max=100\r\n"));
+    }
+
+    #[test]
+    fn diff_equal_responses() {
+        let result = Response::html("<h1>Hello</h1>").diff(&Response::html("<h1>Hello</h1>"));
+
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn diff_status_and_header() {
+        let base = Response::html("<h1>Hello</h1>");
+        let other = Response::html("<h1>Hello</h1>")
+            .status(Status::NotFound)
+            .header(Header::new("Cache-Control", "no-cache"));
+        let result = base.diff(&other);
+        let expected = vec![
+            "status: 200 OK != 404 NOT FOUND".to_string(),
+            "header cache-control: [] != [\"no-cache\"]".to_string(),
+        ];
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn render_template() {
+        let result = Response::render(
+            "<h1>Hello, {{name}}!</h1><p>{{message}}</p>",
+            &[("name", "world"), ("message", "<b>Welcome</b>")],
+        );
+        let expected = "<h1>Hello, world!</h1><p>&lt;b&gt;Welcome&lt;/b&gt;</p>";
+
+        assert_eq!(result.get_content(), expected.as_bytes());
+        assert!(result.to_string().contains("Content-Type: text/html"));
+        assert!(result.to_string().contains("Content-Length: 55"));
+    }
+
+    #[test]
+    fn render_template_with_unknown_key() {
+        let result = Response::render("<h1>Hello, {{name}}!</h1>", &[("other", "value")]);
+        let expected = "<h1>Hello, {{name}}!</h1>";
+
+        assert_eq!(result.get_content(), expected.as_bytes());
+    }
+
+    #[test]
+    fn set_scheme() {
+        let result = Response::empty().set_scheme("HTTPS");
+
+        assert_eq!(result.scheme(), "HTTPS");
+        assert!(result.to_string().starts_with("HTTPS/1.1 200 OK\r\n"));
+    }
+
+    #[test]
+    fn parse_strict_rejects_ambiguous_length() {
+        let res = "HTTP/1.1 200 OK\r\nContent-Length: 5\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nhello\r\n0\r\n\r\n";
+        let result = Response::parse_strict(res);
+
+        assert!(result.is_err());
+        assert!(Response::parse(res).is_ok());
+    }
+
+    #[test]
+    fn parse_strict_accepts_content_length() {
+        let res = "HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nhello";
+        let result = Response::parse_strict(res);
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn parse_rejects_long_reason_phrase() {
+        let res = format!("HTTP/1.1 200 {}\r\n\r\n", "A".repeat(513));
+        let result = Response::parse(&res);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn parse_with_custom_reason_limit() {
+        let limits = ParseLimits { max_reason_len: 8 };
+        let result = Response::parse_with_limits(b"HTTP/1.1 404 NOT FOUND\r\n\r\n", &limits);
+
+        assert!(result.is_err());
+        assert!(Response::parse_with_limits(b"HTTP/1.1 200 OK\r\n\r\n", &limits).is_ok());
+    }
+
+    #[test]
+    fn set_csp() {
+        let csp = Csp::new()
+            .default_src(&["'self'"])
+            .img_src(&["'self'", "data:"]);
+        let result = Response::empty().csp(csp).to_string();
+
+        assert!(result
+            .contains("Content-Security-Policy: default-src 'self'; img-src 'self' data:\r\n"));
+    }
+
+    #[test]
+    fn set_security_headers() {
+        let result = Response::empty().security_headers().to_string();
+
+        assert!(
+            result.contains("Strict-Transport-Security: max-age=31536000; includeSubDomains\r\n")
+        );
+        assert!(result.contains("X-Content-Type-Options: nosniff\r\n"));
+        assert!(result.contains("X-Frame-Options: DENY\r\n"));
+        assert!(result.contains("Referrer-Policy: no-referrer\r\n"));
+    }
+
+    #[test]
+    fn set_hsts_without_subdomains() {
+        let result = Response::empty().hsts(3600, false).to_string();
+
+        assert!(result.contains("Strict-Transport-Security: max-age=3600\r\n"));
+    }
+
+    #[test]
+    fn map_body_updates_content_length() {
+        let result =
+            Response::content("\u{1f0}", "text/plain").map_body(|body| body.to_uppercase());
+
+        assert_eq!(result.get_content(), "J\u{30c}".as_bytes());
+        assert!(result.to_string().contains("Content-Length: 3\r\n"));
+    }
+
+    #[test]
+    fn http10_keep_alive() {
+        let result = Response::with_protocol("HTTP", "1.0");
+
+        assert!(!result.should_keep_alive());
+        assert!(result
+            .header(Header::new("Connection", "Keep-Alive"))
+            .should_keep_alive());
+    }
+
+    #[test]
+    fn http11_keep_alive() {
+        let result = Response::empty();
+
+        assert!(result.should_keep_alive());
+        assert!(!result
+            .header(Header::new("Connection", "close"))
+            .should_keep_alive());
+    }
+
+    #[test]
+    fn into_error_keeps_headers() {
+        let result = Response::json("{\"hello\": \"world\"}")
+            .header(Header::new("Access-Control-Allow-Origin", "*"))
+            .into_error(Status::InternalServerError, "Something went wrong")
+            .to_string();
+        let expected = "HTTP/1.1 500 INTERNAL SERVER ERROR\r\nContent-Type: text/plain\r\nContent-Length: 20\r\nAccess-Control-Allow-Origin: *\r\n\r\nSomething went wrong";
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn typed_header_getters() {
+        let res = "HTTP/1.1 303 SEE OTHER\r\nLocation: /home\r\nETag: \"abc\"\r\ncontent-type: text/plain\r\nContent-Length: 5\r\n\r\nhello";
+        let result = Response::parse(res).unwrap();
+
+        assert_eq!(result.content_length(), Some(5));
+        assert_eq!(result.content_type(), Some("text/plain"));
+        assert_eq!(result.location(), Some("/home"));
+        assert_eq!(result.etag(), Some("\"abc\""));
+    }
+
+    #[test]
+    fn missing_content_length() {
+        let result = Response::body("hello");
+
+        assert_eq!(result.content_length(), None);
+        assert_eq!(result.content_type(), None);
+    }
+
+    #[test]
+    fn malformed_content_length() {
+        let result = Response::body("hello").header(Header::new("Content-Length", "five"));
+
+        assert_eq!(result.content_length(), None);
+    }
+
+    #[test]
+    fn stream_body_from_reader() {
+        let len = 1024 * 1024;
+        let response = Response::streaming(vec![
+            Header::new("Content-Type", "application/octet-stream"),
+            Header::new("Content-Length", &len.to_string()),
+        ]);
+        let head_len = response.head_string().len();
+        let mut reader = std::io::repeat(b'a').take(len as u64);
+        let mut writer: Vec<u8> = Vec::new();
+
+        let result = response.write_body_from(&mut reader, &mut writer).unwrap();
+
+        assert_eq!(result, len as u64);
+        assert_eq!(writer.len(), head_len + len);
+        assert!(writer.starts_with(b"HTTP/1.1 200 OK\r\n"));
+    }
+
+    #[test]
+    fn derive_response_with_status() {
+        let base = Response::html("<h1>Not here</h1>").header(Header::new("X-Request-Id", "42"));
+        let result = base.with_status(Status::NotFound);
+
+        assert!(result.to_string().starts_with("HTTP/1.1 404 NOT FOUND\r\n"));
+        assert!(result.to_string().contains("X-Request-Id: 42\r\n"));
+        assert!(base.to_string().starts_with("HTTP/1.1 200 OK\r\n"));
+    }
+
+    #[test]
+    fn early_hints_interim_response() {
+        let result = Response::interim(
+            Status::EarlyHints,
+            vec![
+                Header::new("Link", "</style.css>; rel=preload; as=style"),
+                Header::new("Link", "</script.js>; rel=preload; as=script"),
+            ],
+        )
+        .unwrap();
+        let expected = "HTTP/1.1 103 EARLY HINTS\r\nLink: </style.css>; rel=preload; as=style\r\nLink: </script.js>; rel=preload; as=script\r\n\r\n";
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn interim_response_with_final_status() {
+        let result = Response::interim(Status::Ok, vec![]);
+
+        assert_eq!(result.unwrap_err().err_type, ErrorType::InvalidCode);
+    }
+
+    #[test]
+    fn empty_response_content_length() {
+        let result = Response::empty().to_string();
+        let expected = "HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n";
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn strip_hop_by_hop_headers() {
+        let result = Response::content("hello", "text/plain")
+            .header(Header::new("Connection", "keep-alive, X-Custom-Hop"))
+            .header(Header::new("Keep-Alive", "timeout=5"))
+            .header(Header::new("TE", "trailers"))
+            .header(Header::new("Proxy-Authenticate", "Basic"))
+            .header(Header::new("X-Custom-Hop", "1"))
+            .strip_hop_by_hop()
+            .unwrap()
+            .to_string();
+        let expected =
+            "HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: 5\r\n\r\nhello";
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn strip_hop_by_hop_from_chunked_response() {
+        let res = "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nhello\r\n0\r\n\r\n";
+        let result = Response::parse(res)
+            .unwrap()
+            .strip_hop_by_hop()
+            .unwrap()
+            .to_string();
+        let expected = "HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nhello";
+
+        assert_eq!(result, expected);
+        assert!(Response::body("zz\r\nhello\r\n0\r\n\r\n")
+            .header(Header::new("Transfer-Encoding", "chunked"))
+            .strip_hop_by_hop()
+            .is_err());
+    }
+
+    #[test]
+    fn switching_protocols_response() {
+        let result = Response::switching_protocols("websocket").to_string();
+        let expected =
+            "HTTP/1.1 101 SWITCHING PROTOCOLS\r\nConnection: Upgrade\r\nUpgrade: websocket\r\n\r\n";
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn websocket_handshake_response() {
+        let result = Response::websocket_handshake("dGhlIHNhbXBsZSBub25jZQ==").to_string();
+        let expected = "HTTP/1.1 101 SWITCHING PROTOCOLS\r\nConnection: Upgrade\r\nUpgrade: websocket\r\nSec-WebSocket-Accept: s3pPLMBiTxaQ9kYGzzhZRbK+xOo=\r\n\r\n";
+
+        assert_eq!(result, expected);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn pretty_json_response() {
+        let value = serde_json::json!({"hello": "world", "list": [1, 2]});
+        let result = Response::json_pretty(&value).unwrap();
+        let expected = "{\n  \"hello\": \"world\",\n  \"list\": [\n    1,\n    2\n  ]\n}";
+
+        assert_eq!(result.get_content(), expected.as_bytes());
+        assert_eq!(result.content_type(), Some("application/json"));
+        assert_eq!(result.content_length(), Some(expected.len() as u64));
+    }
+
+    #[test]
+    fn redirect_with_html_body() {
+        let result = Response::redirect_with_body("/login?next=/home&x=1", Status::SeeOther);
+        let expected = "<a href=\"/login?next=/home&amp;x=1\">/login?next=/home&amp;x=1</a>";
+
+        assert_eq!(result.location(), Some("/login?next=/home&x=1"));
+        assert_eq!(result.get_content(), expected.as_bytes());
+        assert_eq!(result.content_type(), Some("text/html"));
+        assert_eq!(result.content_length(), Some(expected.len() as u64));
+    }
+
+    #[test]
+    fn parse_many_responses() {
+        let res = "HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nhelloHTTP/1.1 204 NO CONTENT\r\n\r\nHTTP/1.1 404 NOT FOUND\r\nContent-Length: 9\r\n\r\nnot";
+        let (result, rest) = Response::parse_many(res).unwrap();
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].get_content(), b"hello");
+        assert_eq!(result[1].status, Status::NoContent);
+        assert_eq!(
+            rest,
+            "HTTP/1.1 404 NOT FOUND\r\nContent-Length: 9\r\n\r\nnot"
+        );
+    }
+
+    #[test]
+    fn parse_many_without_content_length() {
+        let res = "HTTP/1.1 200 OK\r\n\r\nhello";
+        let result = Response::parse_many(res);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn parse_many_with_length_inside_character() {
+        let res = "HTTP/1.1 200 OK\r\nContent-Length: 1\r\n\r\n\u{e9}";
+        let result = Response::parse_many(res);
+
+        assert_eq!(result.unwrap_err().err_type, ErrorType::ParserError);
+    }
+
+    #[test]
+    fn set_age() {
+        let result = Response::empty().age(30).age(60).to_string();
+
+        assert!(result.contains("Age: 60\r\n"));
+        assert!(!result.contains("Age: 30"));
+    }
+
+    #[test]
+    fn downgrade_chunked_response() {
+        let res = "HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nhello\r\n7\r\n, world\r\n0\r\n\r\n";
+        let result = Response::parse(res)
+            .unwrap()
+            .downgrade_to_http10()
+            .unwrap()
+            .to_string();
+        let expected = "HTTP/1.0 200 OK\r\nContent-Type: text/plain\r\nContent-Length: 12\r\nConnection: close\r\n\r\nhello, world";
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn downgrade_invalid_chunked_response() {
+        let result = Response::body("zz\r\nhello\r\n0\r\n\r\n")
+            .header(Header::new("Transfer-Encoding", "chunked"))
+            .downgrade_to_http10();
+
+        assert_eq!(result.unwrap_err().err_type, ErrorType::ParserError);
+    }
+
+    #[test]
+    fn downgrade_keep_alive_response() {
+        let result = Response::empty()
+            .header(Header::new("Connection", "keep-alive"))
+            .downgrade_to_http10()
+            .unwrap()
+            .to_string();
+        let expected = "HTTP/1.0 200 OK\r\nConnection: keep-alive\r\nContent-Length: 0\r\n\r\n";
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn set_custom_reason_phrase() {
+        let result = Response::empty()
+            .status(Status::NotFound)
+            .reason("Nothing Here")
+            .unwrap()
+            .to_string();
+
+        assert!(result.starts_with("HTTP/1.1 404 Nothing Here\r\n"));
+    }
+
+    #[test]
+    fn custom_reason_phrase_with_newline() {
+        let result = Response::empty()
+            .status(Status::NotFound)
+            .reason("Nothing Here\r\nSet-Cookie: session=evil");
+
+        assert_eq!(result.unwrap_err().err_type, ErrorType::InvalidReason);
+    }
+
+    #[test]
+    fn parsed_reason_phrase_is_serialized() {
+        let res = "HTTP/1.1 200 Everything Is Fine\r\nContent-Length: 0\r\n\r\n";
+        let result = Response::parse(res).unwrap();
+
+        assert_eq!(result.to_string(), res);
+        assert!(result
+            .status(Status::NotFound)
+            .to_string()
+            .starts_with("HTTP/1.1 404 NOT FOUND\r\n"));
+    }
+
+    #[test]
+    fn event_stream_response() {
+        let result = Response::event_stream().to_string();
+        let expected =
+            "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n";
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn large_content_length() {
+        let result =
+            Response::parse("HTTP/1.1 200 OK\r\nContent-Length: 5000000000\r\n\r\n").unwrap();
+
+        assert_eq!(result.content_length(), Some(5000000000));
+    }
+
+    #[test]
+    fn overflowing_content_length() {
+        let result =
+            Response::parse("HTTP/1.1 200 OK\r\nContent-Length: 18446744073709551616\r\n\r\n")
+                .unwrap();
+
+        assert_eq!(result.content_length(), None);
+    }
+
+    #[test]
+    fn not_modified_from_ok() {
+        let result = Response::content("hello", "text/plain")
+            .header(Header::new("ETag", "\"abc\""))
+            .header(Header::new(
+                "Last-Modified",
+                "Sun, 06 Nov 1994 08:49:37 GMT",
+            ))
+            .header(Header::new("Cache-Control", "max-age=60"))
+            .to_not_modified()
+            .to_string();
+        let expected = "HTTP/1.1 304 NOT MODIFIED\r\nETag: \"abc\"\r\nLast-Modified: Sun, 06 Nov 1994 08:49:37 GMT\r\nCache-Control: max-age=60\r\n\r\n";
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn unframed_body_gains_content_length() {
+        let result = Response::with_protocol("HTTP", "1.1")
+            .map_body(|_| "hello".to_string())
+            .to_string();
+        let expected = "HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nhello";
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn no_content_does_not_gain_content_length() {
+        let result = Response::with_protocol("HTTP", "1.1")
+            .status(Status::NoContent)
+            .map_body(|_| "hello".to_string())
+            .head_string();
+        let expected = "HTTP/1.1 204 NO CONTENT\r\n\r\n";
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn merge_responses() {
+        let base = Response::content("base", "text/plain")
+            .header(Header::new("X-Request-Id", "42"))
+            .header(Header::new("Cache-Control", "no-store"));
+        let overlay = Response::content("not found", "text/html")
+            .status(Status::NotFound)
+            .header(Header::new("Cache-Control", "max-age=60"));
+        let result = base.merge(overlay).to_string();
+        let expected = "HTTP/1.1 404 NOT FOUND\r\nX-Request-Id: 42\r\nContent-Type: text/html\r\nContent-Length: 9\r\nCache-Control: max-age=60\r\n\r\nnot found";
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn parse_lenient_skips_invalid_header() {
+        let (result, warnings) = Response::parse_lenient(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nnot a header\r\n\r\nhello",
+        );
+        let result = result.unwrap();
+
+        assert_eq!(result.status, Status::Ok);
+        assert_eq!(result.content_type(), Some("text/plain"));
+        assert_eq!(result.get_content(), b"hello");
+        assert_eq!(
+            warnings,
+            vec!["Skipped invalid header line \"not a header\""]
+        );
+    }
+
+    #[test]
+    fn parse_lenient_defaults_missing_status() {
+        let (result, warnings) = Response::parse_lenient("HTTP/1.1\r\n\r\n");
+
+        assert_eq!(result.unwrap().status, Status::Ok);
+        assert_eq!(
+            warnings,
+            vec!["Missing or invalid status code, defaulting to 200"]
+        );
+    }
+
+    #[test]
+    fn parse_lenient_invalid_reason_phrase() {
+        let (result, warnings) = Response::parse_lenient("HTTP/1.1 404 Not\x07Found\r\n\r\n");
+        let result = result.unwrap();
+
+        assert_eq!(result.status, Status::NotFound);
+        assert_eq!(result.reason_phrase(), "NOT FOUND");
+        assert_eq!(
+            warnings,
+            vec!["Invalid reason phrase \"Not\\u{7}Found\", using the default phrase"]
+        );
+    }
+
+    #[test]
+    fn parse_lenient_empty_buffer() {
+        let (result, warnings) = Response::parse_lenient("");
+
+        assert_eq!(result, None);
+        assert_eq!(warnings.len(), 1);
+    }
+
+    #[test]
+    fn error_response() {
+        let result = Response::error(Status::BadRequest, "missing name");
+
+        assert_eq!(result.status, Status::BadRequest);
+        assert_eq!(result.content_type(), Some("text/plain"));
+        assert_eq!(result.content_length(), Some(12));
+        assert_eq!(result.get_content(), b"missing name");
+    }
+
+    #[test]
+    fn multiple_links() {
+        let result = Response::empty()
+            .link("/page/2", "next")
+            .and_then(|res| res.link("/page/9", "last"))
+            .unwrap();
+        let expected = vec![Header::new(
+            "Link",
+            "</page/2>; rel=\"next\", </page/9>; rel=\"last\"",
+        )];
+
+        assert_eq!(result.headers, expected);
+    }
+
+    #[test]
+    fn invalid_links() {
+        assert!(Response::empty().link("/a>b", "next").is_err());
+        assert!(Response::empty().link("/a", "ne\"xt").is_err());
+        assert!(Response::empty()
+            .link("/a\r\nSet-Cookie: session=evil", "next")
+            .is_err());
+        assert!(Response::empty().link("/a", "next\r\nX: 1").is_err());
+    }
+
+    #[test]
+    fn write_in_chunks() {
+        let response = Response::content("hello world", "text/plain");
+        let mut result = Vec::new();
+        response.write_chunked_to(&mut result, 3).unwrap();
+        let expected = response.to_bytes();
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn write_in_zero_size_chunks() {
+        let result = Response::empty().write_chunked_to(&mut Vec::new(), 0);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn parse_only_status_line() {
+        let result = Response::parse_status_line("HTTP/1.1 502 BAD GATEWAY\r\nbroken header\r\n");
+        let expected = Ok(("HTTP/1.1".to_string(), Status::BadGateway));
+
+        assert_eq!(result, expected);
+    }
+
+    #[cfg(feature = "digest")]
+    #[test]
+    fn content_digest_header() {
+        let result = Response::content("hello", "text/plain").content_digest();
+        let expected = "sha-256=LPJNul+wow4m6DsqxbninhsWHlwfp0JecwQzYpOLmCQ=";
+
+        assert_eq!(result.find_header("Digest"), Some(expected));
+    }
+
+    #[test]
+    fn no_content_is_sent_without_body() {
+        let result = Response::content("hello", "text/plain")
+            .status(Status::NoContent)
+            .to_string();
+        let expected = "HTTP/1.1 204 NO CONTENT\r\nContent-Type: text/plain\r\n\r\n";
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn content_length_override() {
+        let result = Response::content("hello", "text/plain")
+            .with_content_length_override(1024)
+            .to_string();
+        let expected =
+            "HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: 1024\r\n\r\nhello";
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn matches_exact_etag() {
+        let result = Response::empty().header(Header::new("ETag", "\"v1\""));
+
+        assert!(result.matches_etag("\"v0\", \"v1\""));
+        assert!(result.matches_etag("W/\"v1\""));
+    }
+
+    #[test]
+    fn matches_wildcard_etag() {
+        let result = Response::empty();
+
+        assert!(result.matches_etag("*"));
+    }
+
+    #[test]
+    fn weak_etag_mismatch() {
+        let result = Response::empty().header(Header::new("ETag", "W/\"v1\""));
+
+        assert!(!result.matches_etag("\"v2\""));
+        assert!(result.matches_etag("\"v1\""));
+    }
+
+    #[test]
+    fn content_with_content_type_constant() {
+        let body = "{\"hello\": \"world\"}";
+        let result = Response::content(body, content_type::JSON);
+        let expected = Response::json(body);
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn multibyte_content_length() {
+        let result = Response::content("héllo", "text/plain");
+
+        assert_eq!(result.content_length(), Some(6));
+        assert_eq!(result.content_len(), 6);
+        assert_eq!(result.char_count(), 5);
+    }
+
+    #[test]
+    fn send_response() {
+        let response = Response::content("hello", "text/plain");
+        let mut result = Vec::new();
+        response.send(&mut result).unwrap();
+        let expected = response.to_bytes();
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn parse_malformed_responses() {
+        let buffers = [
+            "",
+            "\r\n\r\n",
+            "HTTP",
+            "HTTP/",
+            "/1.1 200 OK",
+            "HTTP/1.1",
+            "HTTP/1.1 OK",
+            "HTTP/1.1 abc OK",
+            "HTTP/1.1 99 OK",
+            "HTTP/1.1 70000 OK",
+            "\u{0}\u{1}\u{7f}\u{fffd}",
+            ": \r\n\r\n",
+        ];
+
+        for buffer in buffers {
+            assert!(Response::parse(buffer).is_err(), "{:?}", buffer);
+        }
+        assert!(Response::parse_bytes(b"HTTP/1.1 200 \xff\r\n\r\n").is_err());
+    }
+
+    #[test]
+    fn parse_skips_headers_without_name() {
+        let result = Response::parse("HTTP/1.1 200 OK\r\n: value\r\nno colon\r\n\r\n").unwrap();
+
+        assert!(result.headers.is_empty());
+    }
+
+    #[test]
+    fn parse_many_with_overflowing_length() {
+        let buffer = "HTTP/1.1 200 OK\r\nContent-Length: 18446744073709551615\r\n\r\nhello";
+        let (responses, rest) = Response::parse_many(buffer).unwrap();
+
+        assert!(responses.is_empty());
+        assert_eq!(rest, buffer);
+    }
+
+    #[test]
+    fn self_delimiting_responses() {
+        let content_length = Response::content("hello", "text/plain");
+        let chunked = Response::streaming(vec![Header::new("Transfer-Encoding", "chunked")]);
+        let no_content = Response::with_protocol("HTTP", "1.1").status(Status::NoContent);
+        let unframed_body = Response::with_protocol("HTTP", "1.1").map_body(|_| "hi".to_string());
+
+        assert!(content_length.is_self_delimiting());
+        assert!(chunked.is_self_delimiting());
+        assert!(no_content.is_self_delimiting());
+        assert!(unframed_body.is_self_delimiting());
+    }
+
+    #[test]
+    fn not_self_delimiting_responses() {
+        let streaming = Response::streaming(vec![Header::new("Connection", "close")]);
+        let gzip = Response::streaming(vec![Header::new("Transfer-Encoding", "gzip")])
+            .map_body(|_| "hi".to_string());
+        let invalid_length =
+            Response::parse("HTTP/1.1 200 OK\r\nContent-Length: abc\r\n\r\nhello").unwrap();
+
+        assert!(!streaming.is_self_delimiting());
+        assert!(!gzip.is_self_delimiting());
+        assert!(!invalid_length.is_self_delimiting());
+    }
+
+    #[test]
+    fn vary_cache_key() {
+        let response = Response::empty().header(Header::new("Vary", "Accept-Encoding"));
+        let gzip = [
+            Header::new("Accept-Encoding", "gzip"),
+            Header::new("User-Agent", "curl/7.81.0"),
+        ];
+        let result = response.vary_key(&gzip);
+        let expected = Some("accept-encoding: gzip\n".to_string());
+
+        assert_eq!(result, expected);
+        assert_ne!(
+            result,
+            response.vary_key(&[Header::new("Accept-Encoding", "br")])
+        );
+    }
+
+    #[test]
+    fn vary_wildcard_cache_key() {
+        let response = Response::empty().header(Header::new("Vary", "*"));
+
+        assert_eq!(response.vary_key(&[]), None);
+    }
+
+    #[test]
+    fn short_body_preview() {
+        let result = Response::content("hello", "text/plain").body_preview(10);
+        let expected = "hello";
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn long_multibyte_body_preview() {
+        let result = Response::content("héllo wörld", "text/plain").body_preview(2);
+        let expected = "h...";
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn content_type_for_json_extension() {
+        let result = Response::body("{}").content_type_for_extension("json");
+
+        assert_eq!(result.content_type(), Some("application/json"));
+    }
+
+    #[test]
+    fn content_type_for_unknown_extension() {
+        let result = Response::html("<p>hello</p>").content_type_for_extension("unknown");
+        let expected = vec![
+            Header::new("Content-Type", "application/octet-stream"),
+            Header::new("Content-Length", "12"),
+        ];
+
+        assert_eq!(result.headers, expected);
+    }
+
+    #[test]
+    fn count_cookies() {
+        let result = Response::empty()
+            .cookie("a=1")
+            .cookie("b=2")
+            .header(Header::new("set-cookie", "c=3"));
+
+        assert_eq!(result.cookie_count(), 3);
+        assert!(result.check_cookie_limit(3).is_ok());
+        assert_eq!(
+            result.check_cookie_limit(2).unwrap_err().err_type,
+            ErrorType::InvalidHeader
+        );
+    }
+
+    #[test]
+    fn parse_status_line_with_extra_spaces() {
+        let result = Response::parse("HTTP/1.1  200   OK\r\n\r\n").unwrap();
+
+        assert_eq!(result.status, Status::Ok);
+        assert_eq!(result.reason_phrase(), "OK");
+    }
+
+    #[test]
+    fn trace_echo_response() {
+        let request = Request::from_string(
+            "TRACE /debug HTTP/1.1\r\nHost: localhost:3333\r\nCookie: session=secret\r\n",
+        )
+        .unwrap();
+        let result = Response::trace_echo(&request);
+        let expected = "TRACE /debug HTTP/1.1\r\nHost: localhost:3333\r\n\r\n";
+
+        assert_eq!(result.content_type(), Some("message/http"));
+        assert_eq!(result.content_length(), Some(expected.len() as u64));
+        assert_eq!(result.get_content(), expected.as_bytes());
+    }
+
+    #[test]
+    fn try_header_framing_conflict() {
+        let chunked = Response::streaming(vec![Header::new("Transfer-Encoding", "chunked")]);
+        let result = chunked
+            .try_header(Header::new("Content-Length", "5"))
+            .unwrap_err();
+
+        assert_eq!(result.err_type, ErrorType::InvalidHeader);
+        assert!(Response::content("hello", "text/plain")
+            .try_header(Header::new("Transfer-Encoding", "gzip, chunked"))
+            .is_err());
+    }
+
+    #[test]
+    fn try_header_chunked_empty_response() {
+        let result = Response::empty()
+            .try_header(Header::new("Transfer-Encoding", "chunked"))
+            .unwrap()
+            .to_string();
+        let expected = "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n";
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn try_header_replaces_content_length() {
+        let result = Response::content("hello", "text/plain")
+            .try_header(Header::new("Content-Length", "3"))
+            .unwrap();
+        let expected = vec![
+            Header::new("Content-Type", "text/plain"),
+            Header::new("Content-Length", "3"),
+        ];
+
+        assert_eq!(result.headers, expected);
+        assert_eq!(
+            Response::empty()
+                .try_header(Header::new("Content-Length", "5"))
+                .unwrap()
+                .head_string(),
+            "HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\n"
+        );
+    }
+
+    #[test]
+    fn non_canonical_reason_phrase() {
+        let result = Response::parse("HTTP/1.1 404 Nothing To See Here\r\n\r\n").unwrap();
+
+        assert_eq!(result.status, Status::NotFound);
+        assert_eq!(result.reason_phrase(), "Nothing To See Here");
+        assert_eq!(Response::empty().reason_phrase(), "OK");
+    }
+
+    #[test]
+    fn redirect_absolute_joins_slashes() {
+        let result =
+            Response::redirect_absolute("https://example.com/", "/login", Status::SeeOther);
+        let expected = Some("https://example.com/login");
+
+        assert_eq!(result.location(), expected);
+    }
+
+    #[test]
+    fn redirect_absolute_adds_slash() {
+        let result =
+            Response::redirect_absolute("https://example.com/app", "login", Status::SeeOther);
+        let expected = Some("https://example.com/app/login");
+
+        assert_eq!(result.location(), expected);
+    }
+
+    #[test]
+    fn compressed_variant() {
+        let original = Response::content(&"hello world, ".repeat(100), "text/plain");
+        let result = original.with_compressed_variant().unwrap();
+
+        assert_eq!(original.find_header("Content-Encoding"), None);
+        assert_eq!(original.content_len(), 1300);
+        assert_eq!(result.find_header("Content-Encoding"), Some("gzip"));
+        assert_eq!(result.content_length(), Some(result.content_len() as u64));
+        assert!(result.content_len() < original.content_len());
+        assert_eq!(result.get_content()[..2], [0x1f, 0x8b]);
+        assert_eq!(result.find_header("Vary"), Some("Accept-Encoding"));
+    }
+
+    #[test]
+    fn compressed_variant_of_incompressible_body() {
+        let original = Response::content("hello", "text/plain");
+        let result = original.with_compressed_variant().unwrap();
+
+        assert_eq!(result, original);
+    }
+
+    #[test]
+    fn compressed_variant_etag() {
+        let original = Response::content(&"hello world, ".repeat(100), "text/plain")
+            .header(Header::new("ETag", "W/\"v1\""))
+            .header(Header::new("Vary", "Origin"));
+        let result = original.with_compressed_variant().unwrap();
+
+        assert_eq!(original.etag(), Some("W/\"v1\""));
+        assert_eq!(result.etag(), Some("W/\"v1-gzip\""));
+        assert_eq!(result.find_header("Vary"), Some("Origin, Accept-Encoding"));
+    }
+
+    #[test]
+    fn compressed_variant_of_encoded_body() {
+        let result = Response::content("hello", "text/plain")
+            .header(Header::new("Content-Encoding", "br"))
+            .with_compressed_variant();
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn default_not_found_page() {
+        let result = Response::default_page(Status::NotFound);
+        let expected = "HTTP/1.1 404 NOT FOUND\r\nContent-Type: text/html\r\nContent-Length: 22\r\n\r\n<h1>404 NOT FOUND</h1>";
+
+        assert_eq!(result.to_string(), expected);
+    }
+
+    #[test]
+    fn https_scheme_is_serialized() {
+        let result = Response::empty().set_scheme(Scheme::Https).to_string();
+        let expected = "HTTPS/1.1 200 OK\r\nContent-Length: 0\r\n\r\n";
+
+        assert_eq!(result, expected);
+        assert_eq!(Response::empty().set_scheme("https").scheme(), "HTTPS");
+    }
+
+    #[test]
+    fn parse_connection_tokens() {
+        let result = Response::empty()
+            .header(Header::new("Connection", "keep-alive, Upgrade"))
+            .connection_tokens();
+        let expected = vec!["keep-alive", "upgrade"];
 
         assert_eq!(result, expected);
     }
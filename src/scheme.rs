@@ -0,0 +1,65 @@
+use std::fmt;
+
+/// The scheme of a http message, as written in the start line, e.g. the `HTTP` in `HTTP/1.1`.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub enum Scheme {
+    /// Plain http, written as `HTTP`.
+    #[default]
+    Http,
+    /// Http over TLS, written as `HTTPS`.
+    Https,
+    /// Any other scheme, written exactly as given.
+    Other(String),
+}
+
+impl Scheme {
+    /// Get the representation of the scheme in the start line. The `Http` and `Https` schemes
+    /// are uppercase, e.g. `HTTPS`, and other schemes are kept as they were given.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Scheme::Http => "HTTP",
+            Scheme::Https => "HTTPS",
+            Scheme::Other(scheme) => scheme,
+        }
+    }
+}
+
+impl From<&str> for Scheme {
+    /// Parse a scheme, ignoring case for `http` and `https`. Any other scheme is kept as
+    /// `Scheme::Other`.
+    fn from(scheme: &str) -> Self {
+        if scheme.eq_ignore_ascii_case("HTTP") {
+            Scheme::Http
+        } else if scheme.eq_ignore_ascii_case("HTTPS") {
+            Scheme::Https
+        } else {
+            Scheme::Other(scheme.to_string())
+        }
+    }
+}
+
+impl fmt::Display for Scheme {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Scheme;
+
+    #[test]
+    fn scheme_from_str() {
+        assert_eq!(Scheme::from("http"), Scheme::Http);
+        assert_eq!(Scheme::from("HTTPS"), Scheme::Https);
+        assert_eq!(Scheme::from("RTSP"), Scheme::Other("RTSP".to_string()));
+    }
+
+    #[test]
+    fn default_scheme() {
+        let result = Scheme::default().to_string();
+        let expected = "HTTP";
+
+        assert_eq!(result, expected);
+    }
+}
@@ -0,0 +1,50 @@
+/// Format a server-sent event frame with an optional event name, e.g.
+/// `event: update\ndata: hello\n\n`. Each line of the data is sent as its own `data:` line.
+/// Line breaks are removed from the event name, since they would start a new field.
+pub fn sse_event(event: Option<&str>, data: &str) -> String {
+    let mut frame = String::new();
+
+    if let Some(event) = event {
+        let event: String = event
+            .chars()
+            .filter(|c| !matches!(c, '\r' | '\n'))
+            .collect();
+        frame.push_str(&format!("event: {}\n", event));
+    }
+
+    for line in data.split('\n') {
+        frame.push_str(&format!("data: {}\n", line.trim_end_matches('\r')));
+    }
+
+    frame.push('\n');
+    frame
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sse_event;
+
+    #[test]
+    fn format_event() {
+        let result = sse_event(Some("update"), "hello");
+        let expected = "event: update\ndata: hello\n\n";
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn format_multi_line_event() {
+        let result = sse_event(None, "first line\nsecond line");
+        let expected = "data: first line\ndata: second line\n\n";
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn format_event_with_newline_in_name() {
+        let result = sse_event(Some("a\r\ndata: evil\n\nb"), "x");
+        let expected = "event: adata: evilb\ndata: x\n\n";
+
+        assert_eq!(result, expected);
+    }
+}
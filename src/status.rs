@@ -1,80 +1,203 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::{Error, ErrorType};
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum Status {
+    Continue,
     SwitchingProtocols,
+    EarlyHints,
     Ok,
+    NoContent,
     SeeOther,
+    NotModified,
     NotFound,
     InternalServerError,
     BadRequest,
     Unauthorized,
     Forbidden,
     NotAllowed,
+    RequestTimeout,
+    BadGateway,
+    GatewayTimeout,
+    Custom { code: u16, reason: String },
 }
 
 impl Status {
+    /// Create a new `Status` from a numeric code and reason phrase. Returns the matching variant
+    /// for a supported code, and a `Custom` status carrying the reason phrase otherwise. The code
+    /// must have three digits, and the reason phrase must not contain control characters other
+    /// than tabs.
+    pub fn new(code: u16, reason: &str) -> Result<Self, Error> {
+        let code_err = Error {
+            err_type: ErrorType::InvalidCode,
+            msg: "Status code must have three digits".to_string(),
+        };
+
+        if !(100..=999).contains(&code) {
+            return Err(code_err);
+        }
+
+        validate_reason(reason)?;
+
+        match Self::from_str(&code.to_string()) {
+            Ok(status) => Ok(status),
+            Err(_) => Ok(Status::Custom {
+                code,
+                reason: reason.to_string(),
+            }),
+        }
+    }
+
+    /// Create a new `Status` from a supported numeric status code.
+    pub fn from_u16(code: u16) -> Result<Self, Error> {
+        match Self::from_str(&code.to_string()) {
+            Ok(status) => Ok(status),
+            Err(_) => Err(Error {
+                err_type: ErrorType::InvalidCode,
+                msg: "Invalid or unsupported status code".to_string(),
+            }),
+        }
+    }
+
     /// Get the numeric representation of the status code.
-    fn code(&self) -> u16 {
+    pub fn code(&self) -> u16 {
         match self {
+            Status::Continue => 100,
             Status::SwitchingProtocols => 101,
+            Status::EarlyHints => 103,
             Status::Ok => 200,
+            Status::NoContent => 204,
             Status::SeeOther => 303,
+            Status::NotModified => 304,
             Status::BadRequest => 400,
             Status::Unauthorized => 401,
             Status::Forbidden => 403,
             Status::NotFound => 404,
             Status::NotAllowed => 405,
+            Status::RequestTimeout => 408,
             Status::InternalServerError => 500,
+            Status::BadGateway => 502,
+            Status::GatewayTimeout => 504,
+            Status::Custom { code, .. } => *code,
         }
     }
 
-    fn message(&self) -> &str {
-        // Get the status message.
+    /// Get the status message.
+    pub fn message(&self) -> &str {
         match self {
+            Status::Continue => "CONTINUE",
             Status::SwitchingProtocols => "SWITCHING PROTOCOLS",
+            Status::EarlyHints => "EARLY HINTS",
             Status::Ok => "OK",
+            Status::NoContent => "NO CONTENT",
             Status::SeeOther => "SEE OTHER",
+            Status::NotModified => "NOT MODIFIED",
             Status::BadRequest => "BAD REQUEST",
             Status::Unauthorized => "UNAUTHORIZED",
             Status::Forbidden => "FORBIDDEN",
             Status::NotFound => "NOT FOUND",
             Status::NotAllowed => "NOT ALLOWED",
+            Status::RequestTimeout => "REQUEST TIMEOUT",
             Status::InternalServerError => "INTERNAL SERVER ERROR",
+            Status::BadGateway => "BAD GATEWAY",
+            Status::GatewayTimeout => "GATEWAY TIMEOUT",
+            Status::Custom { reason, .. } => reason,
         }
     }
+
+    /// Check whether a response with this status must not have a body, which is the case for
+    /// informational (`1xx`), `204` and `304` responses.
+    pub(crate) fn forbids_body(&self) -> bool {
+        matches!(self.code(), 100..=199 | 204 | 304)
+    }
+
+    /// Check whether a response with this status can be cached when it has no explicit freshness
+    /// information, as defined in RFC 7231.
+    pub fn is_cacheable_by_default(&self) -> bool {
+        matches!(
+            self.code(),
+            200 | 203 | 204 | 206 | 300 | 301 | 404 | 405 | 410 | 414 | 501
+        )
+    }
+
+    /// Check whether a request which failed with this status is worth retrying, which is the case
+    /// for timeouts, rate limiting and transient server errors.
+    pub fn is_retryable(&self) -> bool {
+        matches!(self.code(), 408 | 425 | 429 | 500 | 502 | 503 | 504)
+    }
 }
 
-impl ToString for Status {
+impl fmt::Display for Status {
     /// Convert the `Status` to a valid http plaintext representation.
-    fn to_string(&self) -> String {
-        format!("{} {}", self.code(), self.message())
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.code(), self.message())
+    }
+}
+
+impl From<Status> for u16 {
+    fn from(status: Status) -> Self {
+        status.code()
+    }
+}
+
+impl TryFrom<u16> for Status {
+    type Error = Error;
+
+    fn try_from(code: u16) -> Result<Self, Self::Error> {
+        Self::from_u16(code)
     }
 }
 
 impl FromStr for Status {
     type Err = Error;
 
+    /// Parse a supported numeric status code, e.g. `200`. Surrounding whitespace is ignored, and
+    /// only numeric codes are accepted, not reason phrases.
     fn from_str(code: &str) -> Result<Self, Self::Err> {
         let parser_err = Error {
             err_type: ErrorType::ParserError,
             msg: "Invalid status format".to_string(),
         };
-        match code {
+        match code.trim() {
+            "100" => Ok(Self::Continue),
             "101" => Ok(Self::SwitchingProtocols),
+            "103" => Ok(Self::EarlyHints),
             "200" => Ok(Self::Ok),
+            "204" => Ok(Self::NoContent),
             "303" => Ok(Self::SeeOther),
+            "304" => Ok(Self::NotModified),
             "400" => Ok(Self::BadRequest),
             "401" => Ok(Self::Unauthorized),
             "403" => Ok(Self::Forbidden),
             "404" => Ok(Self::NotFound),
             "405" => Ok(Self::NotAllowed),
+            "408" => Ok(Self::RequestTimeout),
             "500" => Ok(Self::InternalServerError),
-            _ => Err(parser_err)
+            "502" => Ok(Self::BadGateway),
+            "504" => Ok(Self::GatewayTimeout),
+            _ => Err(parser_err),
         }
     }
 }
 
+/// Check that a reason phrase is valid to send in a status line. Line breaks and other control
+/// characters are rejected, since these could be used to inject headers into the response.
+pub(crate) fn validate_reason(reason: &str) -> Result<(), Error> {
+    if reason.chars().any(|c| c.is_control() && c != '\t') {
+        return Err(Error {
+            err_type: ErrorType::InvalidReason,
+            msg: "Reason phrase must not contain control characters".to_string(),
+        });
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::ErrorType;
     use crate::Status;
 
     #[test]
@@ -100,4 +223,102 @@ mod tests {
 
         assert_eq!(result, expected);
     }
+
+    #[test]
+    fn new_custom_status() {
+        let result = Status::new(599, "CUSTOM REASON").unwrap();
+        let expected = Status::Custom {
+            code: 599,
+            reason: "CUSTOM REASON".to_string(),
+        };
+
+        assert_eq!(result, expected);
+        assert_eq!(result.code(), 599);
+        assert_eq!(result.message(), "CUSTOM REASON");
+        assert_eq!(result.to_string(), "599 CUSTOM REASON");
+    }
+
+    #[test]
+    fn new_known_status() {
+        let result = Status::new(404, "Not Found");
+        let expected = Ok(Status::NotFound);
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn new_invalid_status() {
+        let result = Status::new(42, "TOO SHORT");
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn cacheable_by_default() {
+        let cacheable = [
+            Status::Ok,
+            Status::NoContent,
+            Status::NotFound,
+            Status::NotAllowed,
+        ];
+        let not_cacheable = [
+            Status::SeeOther,
+            Status::BadRequest,
+            Status::Forbidden,
+            Status::InternalServerError,
+        ];
+
+        assert!(cacheable.iter().all(|s| s.is_cacheable_by_default()));
+        assert!(!not_cacheable.iter().any(|s| s.is_cacheable_by_default()));
+    }
+
+    #[test]
+    fn status_into_u16() {
+        let result: u16 = Status::NotFound.into();
+        let expected = 404;
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn status_try_from_u16() {
+        let result = Status::try_from(403);
+        let expected = Ok(Status::Forbidden);
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn status_try_from_unsupported_u16() {
+        let result = Status::try_from(418).unwrap_err();
+
+        assert_eq!(result.err_type, ErrorType::InvalidCode);
+    }
+
+    #[test]
+    fn retryable_status() {
+        let retryable = [408, 425, 429, 500, 502, 503, 504];
+
+        for code in retryable {
+            assert!(Status::new(code, "RETRY").unwrap().is_retryable());
+        }
+        assert_eq!(Status::from_u16(504), Ok(Status::GatewayTimeout));
+        assert!(!Status::Ok.is_retryable());
+        assert!(!Status::NotFound.is_retryable());
+    }
+
+    #[test]
+    fn status_from_str_with_whitespace() {
+        let result = " 200 ".parse::<Status>();
+        let expected = Ok(Status::Ok);
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn custom_status_with_newline_in_reason() {
+        let result = Status::new(599, "CUSTOM\r\nLocation: /evil");
+
+        assert_eq!(result.unwrap_err().err_type, ErrorType::InvalidReason);
+    }
 }
@@ -0,0 +1,112 @@
+/// Percent-encode every byte of a url that is neither an unreserved nor a reserved character, so
+/// the delimiters between the scheme, host, path and query are left intact. Existing `%XX`
+/// escapes are kept as they are, so an already encoded url is not encoded twice.
+pub(crate) fn percent_encode(url: &str) -> String {
+    let bytes = url.as_bytes();
+    let mut encoded = String::new();
+
+    for (i, byte) in bytes.iter().enumerate() {
+        let is_escape = *byte == b'%'
+            && bytes.len() > i + 2
+            && bytes[i + 1].is_ascii_hexdigit()
+            && bytes[i + 2].is_ascii_hexdigit();
+
+        if is_escape || is_unreserved(*byte) || is_reserved(*byte) {
+            encoded.push(*byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+
+    encoded
+}
+
+/// Decode the `%XX` escapes in a url component. Invalid escapes are kept as they are, and bytes
+/// which do not form valid UTF-8 are replaced with the replacement character.
+pub(crate) fn percent_decode(component: &str) -> String {
+    let bytes = component.as_bytes();
+    let mut decoded = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let escape = bytes
+            .get(i + 1..i + 3)
+            .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+        match escape {
+            Some(byte) if bytes[i] == b'%' => {
+                decoded.push(byte);
+                i += 3;
+            }
+            _ => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn is_unreserved(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || b"-._~".contains(&byte)
+}
+
+fn is_reserved(byte: u8) -> bool {
+    b":/?#[]@!$&'()*+,;=".contains(&byte)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::url::{percent_decode, percent_encode};
+
+    #[test]
+    fn encode_space() {
+        let result = percent_encode("https://example.com/hello world?q=a b");
+        let expected = "https://example.com/hello%20world?q=a%20b";
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn encode_unicode() {
+        let result = percent_encode("/café");
+        let expected = "/caf%C3%A9";
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn encode_already_encoded() {
+        let result = percent_encode("https://example.com/hello%20world");
+        let expected = "https://example.com/hello%20world";
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn encode_lone_percent() {
+        let result = percent_encode("/100%");
+        let expected = "/100%25";
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn decode_escapes() {
+        let result = percent_decode("caf%C3%A9%20au%2Flait");
+        let expected = "café au/lait";
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn decode_invalid_escape() {
+        let result = percent_decode("100%zz%+1%");
+        let expected = "100%zz%+1%";
+
+        assert_eq!(result, expected);
+    }
+}